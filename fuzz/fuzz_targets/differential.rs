@@ -0,0 +1,17 @@
+//! Simulate random workloads with the threaded processor and the
+//! deterministic reference and fail if their logs diverge.
+//!
+//! cargo fuzz run differential corpus/differential
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use processor::{compare_engines, FuzzCase};
+
+fuzz_target!(|data: &[u8]| {
+    let case = FuzzCase::from_bytes(data);
+    let scheduler = || case.new_scheduler();
+    if let Err(divergence) = compare_engines(&case.workload, case.options, scheduler) {
+        panic!("{} scheduler: {divergence}", case.scheduler);
+    }
+});
@@ -0,0 +1,148 @@
+//! Offline schedulability analysis of a [`TaskSet`] under rate monotonic
+//! priorities, and its comparison with a simulation.
+
+use crate::periodic::{PeriodicRun, TaskSet};
+
+/// The Liu & Layland utilisation bound of `tasks` periodic tasks,
+/// `n (2^(1/n) - 1)`: rate monotonic priorities meet every deadline of a
+/// task set whose utilisation is at most this bound, if the deadlines are
+/// the periods.
+///
+/// ## Example
+///
+/// ```rust
+/// assert_eq!(processor::liu_layland_bound(1), 1.0);
+/// assert!((processor::liu_layland_bound(2) - 0.828).abs() < 0.001);
+/// ```
+pub fn liu_layland_bound(tasks: usize) -> f64 {
+    if tasks == 0 {
+        return 1.0;
+    }
+    let n = tasks as f64;
+    n * (2f64.powf(1.0 / n) - 1.0)
+}
+
+/// The analysis of a task set whose tasks have rate monotonic priorities.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Analysis {
+    /// The processor utilisation that the tasks declare.
+    pub utilisation: f64,
+
+    /// The [`liu_layland_bound`] of the task set.
+    pub bound: f64,
+
+    /// The worst-case response time of every task, `None` if it exceeds
+    /// the deadline of the task.
+    pub response_times: Vec<Option<usize>>,
+}
+
+/// The predicted and the simulated worst-case response time of a task.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResponseComparison {
+    /// The index of the task in the [`TaskSet`].
+    pub task: usize,
+
+    /// The worst-case response time from the analysis, `None` if the task
+    /// may miss its deadline.
+    pub predicted: Option<usize>,
+
+    /// The longest response time of a job in the simulation, `None` if
+    /// the task has not completed any job.
+    pub observed: Option<usize>,
+}
+
+impl ResponseComparison {
+    /// Whether the simulation stays within the prediction.
+    pub fn holds(&self) -> bool {
+        match (self.predicted, self.observed) {
+            (Some(predicted), Some(observed)) => observed <= predicted,
+            _ => true,
+        }
+    }
+}
+
+impl Analysis {
+    /// Analyse the task set with the runtimes, deadlines and periods that
+    /// the tasks declare. A task has a higher priority than the ones with
+    /// a longer period, and the tasks with the same period delay each other.
+    ///
+    /// The response time of a task is the smallest `R` with
+    /// `R = C + B + sum(ceil(R / T) * C)` over the tasks with a higher or
+    /// the same priority, where `B` is the `blocking`: the time a process
+    /// with a lower priority can keep the processor after a task is
+    /// released. A simulation with a timeslice `Q` needs `Q - 1`.
+    pub fn rate_monotonic(task_set: &TaskSet, blocking: usize) -> Analysis {
+        let tasks = task_set
+            .tasks
+            .iter()
+            .map(|task| task.reservation)
+            .collect::<Vec<_>>();
+        let response_times = tasks
+            .iter()
+            .enumerate()
+            .map(|(index, task)| {
+                let higher = tasks
+                    .iter()
+                    .enumerate()
+                    .filter(|(other, reservation)| {
+                        *other != index && reservation.period <= task.period
+                    })
+                    .map(|(_, reservation)| reservation)
+                    .collect::<Vec<_>>();
+                let deadline = task.deadline.get();
+                let own = task.runtime.get() + blocking;
+                let mut response = own;
+                while response <= deadline {
+                    let next = own
+                        + higher
+                            .iter()
+                            .map(|other| {
+                                response.div_ceil(other.period.get()) * other.runtime.get()
+                            })
+                            .sum::<usize>();
+                    if next == response {
+                        return Some(response);
+                    }
+                    response = next;
+                }
+                None
+            })
+            .collect();
+        Analysis {
+            utilisation: tasks.iter().map(|task| task.utilisation()).sum(),
+            bound: liu_layland_bound(tasks.len()),
+            response_times,
+        }
+    }
+
+    /// Whether the utilisation is within the Liu & Layland bound, which is
+    /// enough but not necessary to meet every deadline.
+    pub fn within_bound(&self) -> bool {
+        self.utilisation <= self.bound
+    }
+
+    /// Whether every task meets its deadlines, from the exact response
+    /// time analysis.
+    pub fn schedulable(&self) -> bool {
+        self.response_times.iter().all(Option::is_some)
+    }
+
+    /// Compare the predicted worst-case response times with the longest
+    /// ones of a simulation of the task set.
+    pub fn compare(&self, run: &PeriodicRun) -> Vec<ResponseComparison> {
+        self.response_times
+            .iter()
+            .enumerate()
+            .map(|(task, predicted)| ResponseComparison {
+                task,
+                predicted: *predicted,
+                observed: run
+                    .jobs
+                    .iter()
+                    .filter(|job| job.task == task)
+                    .map(|job| job.response())
+                    .max(),
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,184 @@
+//! Explaining a deadlock: the blocked processes, the events they wait for
+//! and the processes that would signal them.
+//!
+//! The scheduler only knows what a process waits for. A process declares
+//! the events it will signal with [`Process::will_signal`](crate::Process::will_signal),
+//! [`Workload`](crate::Workload)s do it for every signal they send, which
+//! gives the edges of the wait-for graph.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::{self, Display};
+
+use scheduler::{Pcb, Pid, ProcessState, WaitQueues};
+
+use crate::ProcessInfo;
+
+/// A process that is blocked when the simulation deadlocks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Blocked {
+    /// The PID of the process.
+    pub pid: Pid,
+
+    /// The event the process waits for.
+    pub event: usize,
+
+    /// The processes that have declared they will signal the event.
+    pub signalers: Vec<Pid>,
+}
+
+/// The explanation of a [`SchedulingDecision::Deadlock`](scheduler::SchedulingDecision::Deadlock).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DeadlockReport {
+    /// The blocked processes, by PID.
+    pub blocked: Vec<Blocked>,
+
+    /// Processes that wait for each other, starting from the smallest PID:
+    /// every process waits for an event that the next one would signal and
+    /// the last one waits for the first.
+    pub cycle: Option<Vec<Pid>>,
+}
+
+impl DeadlockReport {
+    /// Explain the deadlock of the `processes`, `signalers` returns the
+    /// processes that would signal an event.
+    pub(crate) fn new(
+        processes: &HashMap<Pid, ProcessInfo>,
+        signalers: impl Fn(usize) -> Vec<Pid>,
+    ) -> DeadlockReport {
+        let mut waiting = processes
+            .values()
+            .filter_map(|process| match process.state {
+                ProcessState::Waiting { event: Some(event) } => Some((process.pid, event)),
+                _ => None,
+            })
+            .collect::<Vec<(Pid, usize)>>();
+        waiting.sort();
+        let mut wait_q = WaitQueues::default();
+        for (pid, event) in waiting {
+            wait_q.push(event, Pcb::new(pid, None, 0, ()));
+        }
+
+        let mut blocked = wait_q
+            .events()
+            .into_iter()
+            .flat_map(|event| {
+                let signaled_by = signalers(event);
+                wait_q.waiters(event).map(move |process| Blocked {
+                    pid: process.pid,
+                    event,
+                    signalers: signaled_by.clone(),
+                })
+            })
+            .collect::<Vec<Blocked>>();
+        blocked.sort_by_key(|process| process.pid);
+
+        let edges = blocked
+            .iter()
+            .map(|process| (process.pid, process.signalers.clone()))
+            .collect::<BTreeMap<Pid, Vec<Pid>>>();
+        let cycle = cycle(&edges);
+        DeadlockReport { blocked, cycle }
+    }
+}
+
+impl Display for DeadlockReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for process in self.blocked.iter() {
+            write!(
+                f,
+                "Process {} waits for event {}",
+                process.pid, process.event
+            )?;
+            if !process.signalers.is_empty() {
+                let signalers = process
+                    .signalers
+                    .iter()
+                    .map(Pid::to_string)
+                    .collect::<Vec<String>>();
+                write!(f, ", signaled by {}", signalers.join(", "))?;
+            }
+            writeln!(f)?;
+        }
+        if let Some(cycle) = &self.cycle {
+            let pids = cycle
+                .iter()
+                .chain(cycle.first())
+                .map(Pid::to_string)
+                .collect::<Vec<String>>();
+            writeln!(f, "Wait-for cycle: {}", pids.join(" -> "))?;
+        }
+        Ok(())
+    }
+}
+
+/// The events that every process has declared it will signal, with the
+/// number of signals left.
+#[derive(Debug, Default)]
+pub(crate) struct Owners(HashMap<usize, BTreeMap<Pid, usize>>);
+
+impl Owners {
+    pub(crate) fn declare(&mut self, pid: Pid, event: usize) {
+        *self.0.entry(event).or_default().entry(pid).or_insert(0) += 1;
+    }
+
+    pub(crate) fn signaled(&mut self, pid: Pid, event: usize) {
+        if let Some(owners) = self.0.get_mut(&event) {
+            if let Some(count) = owners.get_mut(&pid) {
+                *count -= 1;
+                if *count == 0 {
+                    owners.remove(&pid);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn exited(&mut self, pid: Pid) {
+        for owners in self.0.values_mut() {
+            owners.remove(&pid);
+        }
+    }
+
+    pub(crate) fn signalers(&self, event: usize) -> Vec<Pid> {
+        self.0
+            .get(&event)
+            .map(|owners| owners.keys().copied().collect())
+            .unwrap_or_default()
+    }
+}
+
+/// The first cycle found by a depth first search that starts from the
+/// smallest PIDs, rotated to start from its smallest PID.
+fn cycle(edges: &BTreeMap<Pid, Vec<Pid>>) -> Option<Vec<Pid>> {
+    fn visit(
+        pid: Pid,
+        edges: &BTreeMap<Pid, Vec<Pid>>,
+        path: &mut Vec<Pid>,
+        done: &mut BTreeSet<Pid>,
+    ) -> Option<Vec<Pid>> {
+        if let Some(start) = path.iter().position(|other| *other == pid) {
+            return Some(path[start..].to_vec());
+        }
+        if done.contains(&pid) {
+            return None;
+        }
+        path.push(pid);
+        for next in edges.get(&pid).into_iter().flatten() {
+            if let Some(cycle) = visit(*next, edges, path, done) {
+                return Some(cycle);
+            }
+        }
+        path.pop();
+        done.insert(pid);
+        None
+    }
+
+    let mut done = BTreeSet::new();
+    for pid in edges.keys() {
+        if let Some(mut cycle) = visit(*pid, edges, &mut vec![], &mut done) {
+            let smallest = (0..cycle.len()).min_by_key(|index| cycle[*index]).unwrap();
+            cycle.rotate_left(smallest);
+            return Some(cycle);
+        }
+    }
+    None
+}
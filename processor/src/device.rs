@@ -0,0 +1,98 @@
+//! Simulated I/O devices.
+//!
+//! Every device serves the requests it receives in order, one at a time,
+//! so processes that use the same device wait for each other.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::{self, Display};
+
+use scheduler::Pid;
+
+/// Information about a device state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceInfo {
+    /// The device number.
+    pub device: usize,
+
+    /// The processes whose requests are not completed, in the
+    /// order they are served.
+    pub queue: Vec<Pid>,
+
+    /// The number of requests the device has received.
+    pub requests: usize,
+
+    /// The amount of time the device has spent serving requests.
+    pub busy_time: usize,
+}
+
+impl Display for DeviceInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let queue = self
+            .queue
+            .iter()
+            .map(|pid| pid.to_string())
+            .collect::<Vec<String>>()
+            .join(" ");
+        write!(
+            f,
+            "{}\t{}\t{}\t{}",
+            self.device, self.requests, self.busy_time, queue
+        )
+    }
+}
+
+#[derive(Default)]
+struct Device {
+    /// Pending requests, as (pid, completion time).
+    queue: VecDeque<(Pid, usize)>,
+    busy_until: usize,
+    requests: usize,
+    busy_time: usize,
+}
+
+/// The devices of the processor and the current time.
+#[derive(Default)]
+pub(crate) struct Devices {
+    devices: BTreeMap<usize, Device>,
+    now: usize,
+}
+
+impl Devices {
+    /// Advance the time to `now`, completing the requests that finished.
+    pub(crate) fn advance(&mut self, now: usize) {
+        for device in self.devices.values_mut() {
+            device.busy_time += device.busy_until.min(now).saturating_sub(self.now);
+            while let Some(&(_, completion)) = device.queue.front() {
+                if completion > now {
+                    break;
+                }
+                device.queue.pop_front();
+            }
+        }
+        self.now = now;
+    }
+
+    /// Queue a request of `duration` time units on `device` and return the
+    /// time it completes, after the requests queued before it.
+    pub(crate) fn request(&mut self, device: usize, pid: Pid, duration: usize) -> usize {
+        let now = self.now;
+        let device = self.devices.entry(device).or_default();
+        device.busy_until = device.busy_until.max(now) + duration;
+        device.requests += 1;
+        device.queue.push_back((pid, device.busy_until));
+        device.busy_until
+    }
+
+    /// The state of every device that has received requests.
+    pub(crate) fn list(&self) -> Vec<DeviceInfo> {
+        self.devices
+            .iter()
+            .map(|(&number, device)| DeviceInfo {
+                device: number,
+                queue: device.queue.iter().map(|&(pid, _)| pid).collect(),
+                requests: device.requests,
+                busy_time: device.busy_time,
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,324 @@
+//! Random workloads, reproducible from a seed.
+//!
+//! The processes are numbered in the order they are added to the fork tree,
+//! so a parent always has a smaller number than its children. A process only
+//! waits for events signaled by processes with smaller numbers and every
+//! event is signaled with [`Op::SignalLatched`], so a signal that arrives
+//! before the wait is not lost. At the end, every process signals its parent
+//! that it has finished and every parent waits for all its children. The
+//! simulation never deadlocks, unless a deadlock is requested.
+//!
+//! After its children have signaled, the process with PID 1 sleeps for one
+//! time unit, so that they exit before it does. With a scheduler that runs
+//! the ready processes before the ones that wake up later, like round robin,
+//! the simulation ends with [`SchedulingDecision::Done`](scheduler::SchedulingDecision::Done).
+//!
+//! The processes can also be forked with [`Op::ForkClass`] and change their
+//! class with [`Op::SetClass`], or be forked with [`Op::ForkReserved`]. The
+//! classes and the reservations are drawn only if the
+//! [`GeneratorConfig::class_chance`] or the [`GeneratorConfig::reserved_chance`]
+//! is not 0, so the workloads generated without them do not change. The
+//! reservations always fit together, a denied fork would leave its parent
+//! waiting for a child that does not exist.
+
+use std::ops::RangeInclusive;
+
+use scheduler::{Reservation, RtPriority, SchedClass};
+
+use crate::{Op, Workload};
+
+/// The distribution of the generated workloads.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratorConfig {
+    /// The number of processes, including the process with PID 1.
+    pub processes: usize,
+
+    /// The maximum depth of the fork tree, the process with PID 1 has depth 0.
+    pub max_depth: usize,
+
+    /// The number of CPU bursts, sleeps and device requests of every process.
+    pub steps: RangeInclusive<usize>,
+
+    /// The length of a CPU burst.
+    pub burst: RangeInclusive<usize>,
+
+    /// The length of a sleep.
+    pub sleep: RangeInclusive<usize>,
+
+    /// The chance, in percent, that a step is a sleep instead of a CPU burst.
+    pub sleep_chance: u8,
+
+    /// The number of devices, device requests are generated only if it is not 0.
+    pub devices: usize,
+
+    /// The chance, in percent, that a step is a device request instead of a CPU burst.
+    pub io_chance: u8,
+
+    /// The number of wait/signal pairs between processes.
+    pub events: usize,
+
+    /// The priorities of the forked processes are between `-priority_spread`
+    /// and `priority_spread`.
+    pub priority_spread: i8,
+
+    /// The chance, in percent, that a forked process is given a random
+    /// scheduling class, and that a step changes the class of its process.
+    pub class_chance: u8,
+
+    /// The chance, in percent, that a forked process that has no class
+    /// is given a processor reservation.
+    pub reserved_chance: u8,
+
+    /// Make two processes wait for each other, so the simulation ends
+    /// with a [`SchedulingDecision::Deadlock`](scheduler::SchedulingDecision::Deadlock).
+    pub deadlock: bool,
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> GeneratorConfig {
+        GeneratorConfig {
+            processes: 5,
+            max_depth: 3,
+            steps: 1..=4,
+            burst: 1..=10,
+            sleep: 1..=10,
+            sleep_chance: 20,
+            devices: 0,
+            io_chance: 0,
+            events: 3,
+            priority_spread: 0,
+            class_chance: 0,
+            reserved_chance: 0,
+            deadlock: false,
+        }
+    }
+}
+
+/// The SplitMix64 generator, small and stable across versions, so a seed
+/// always generates the same workload.
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Rng {
+        Rng(seed)
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A value in the range, or its start if the range is empty.
+    pub(crate) fn range(&mut self, range: &RangeInclusive<usize>) -> usize {
+        if range.is_empty() {
+            return *range.start();
+        }
+        let width = (range.end() - range.start()) as u64 + 1;
+        range.start() + (self.next_u64() % width) as usize
+    }
+
+    /// A value smaller than `bound`, which has to be greater than 0.
+    pub(crate) fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    pub(crate) fn chance(&mut self, percent: u8) -> bool {
+        self.below(100) < percent as usize
+    }
+}
+
+/// An instruction of a process being generated.
+enum Step {
+    Op(Op),
+    Fork(usize),
+}
+
+/// How a process is forked.
+enum Spawn {
+    Fork,
+    Class(SchedClass),
+    Reserved(Reservation),
+}
+
+struct Node {
+    priority: i8,
+    spawn: Spawn,
+    steps: Vec<Step>,
+    children: Vec<usize>,
+}
+
+impl Workload {
+    /// Generate a random workload.
+    ///
+    /// * `config` - the distribution of the workload.
+    /// * `seed` - the same seed and configuration generate the same workload.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use processor::{GeneratorConfig, Workload};
+    ///
+    /// let config = GeneratorConfig::default();
+    /// assert_eq!(Workload::generate(&config, 7), Workload::generate(&config, 7));
+    /// ```
+    pub fn generate(config: &GeneratorConfig, seed: u64) -> Workload {
+        let mut rng = Rng::new(seed);
+        let count = config.processes.max(1);
+        let mut depths = vec![0];
+        let mut nodes = vec![];
+
+        // the fork tree and the bursts, sleeps and device requests
+        for index in 0..count {
+            let mut steps = vec![];
+            for _ in 0..rng.range(&config.steps) {
+                let op = if config.class_chance > 0 && rng.chance(config.class_chance) {
+                    Op::SetClass(class(&mut rng))
+                } else if rng.chance(config.sleep_chance) {
+                    Op::Sleep(rng.range(&config.sleep).max(1))
+                } else if config.devices > 0 && rng.chance(config.io_chance) {
+                    Op::Io {
+                        device: rng.below(config.devices),
+                        duration: rng.range(&config.burst).max(1),
+                    }
+                } else {
+                    Op::Exec(rng.range(&config.burst).max(1))
+                };
+                steps.push(Step::Op(op));
+            }
+
+            let spread = config.priority_spread.unsigned_abs() as usize;
+            let priority = (rng.below(2 * spread + 1) as isize - spread as isize) as i8;
+            let spawn = if config.class_chance > 0 && rng.chance(config.class_chance) {
+                Spawn::Class(class(&mut rng))
+            } else if config.reserved_chance > 0 && rng.chance(config.reserved_chance) {
+                Spawn::Reserved(reservation(&mut rng, count))
+            } else {
+                Spawn::Fork
+            };
+            nodes.push(Node {
+                priority,
+                spawn,
+                steps,
+                children: vec![],
+            });
+
+            if index > 0 {
+                let parents = (0..index)
+                    .filter(|parent| depths[*parent] < config.max_depth.max(1))
+                    .collect::<Vec<usize>>();
+                let parent = parents[rng.below(parents.len())];
+                depths.push(depths[parent] + 1);
+                nodes[parent].children.push(index);
+                insert(&mut rng, &mut nodes[parent].steps, Step::Fork(index));
+            }
+        }
+
+        // a process waits only for processes with smaller numbers
+        let mut event = 0;
+        if count > 1 {
+            for _ in 0..config.events {
+                event += 1;
+                let waiter = 1 + rng.below(count - 1);
+                let signaler = rng.below(waiter);
+                insert(
+                    &mut rng,
+                    &mut nodes[signaler].steps,
+                    Step::Op(Op::SignalLatched(event)),
+                );
+                insert(
+                    &mut rng,
+                    &mut nodes[waiter].steps,
+                    Step::Op(Op::Wait(event)),
+                );
+            }
+        }
+
+        if config.deadlock {
+            // two processes wait for each other before signaling, after
+            // they have forked their children and signaled their events
+            let first = rng.below(count);
+            let second = if count > 1 {
+                (first + 1 + rng.below(count - 1)) % count
+            } else {
+                first
+            };
+            let (a, b) = (event + 1, event + 2);
+            event += 2;
+            nodes[first].steps.push(Step::Op(Op::Wait(a)));
+            nodes[first].steps.push(Step::Op(Op::SignalLatched(b)));
+            if second != first {
+                nodes[second].steps.push(Step::Op(Op::Wait(b)));
+                nodes[second].steps.push(Step::Op(Op::SignalLatched(a)));
+            }
+        }
+
+        // every parent waits for its children to finish, the event of
+        // a child is signaled after it has waited for its own children
+        for node in nodes.iter_mut() {
+            for child in node.children.iter() {
+                node.steps.push(Step::Op(Op::Wait(event + child)));
+            }
+        }
+        for (index, node) in nodes.iter_mut().enumerate().skip(1) {
+            node.steps.push(Step::Op(Op::SignalLatched(event + index)));
+        }
+        if count > 1 {
+            // the children still have to exit after they have signaled,
+            // a time unit each even if they all have a lower priority
+            nodes[0].steps.push(Step::Op(Op::Sleep(count - 1)));
+        }
+
+        build(&mut nodes, 0)
+    }
+}
+
+/// Insert the step at a random position.
+fn insert(rng: &mut Rng, steps: &mut Vec<Step>, step: Step) {
+    let position = rng.below(steps.len() + 1);
+    steps.insert(position, step);
+}
+
+/// A random scheduling class, the real-time ones with a priority from 1 to 10.
+fn class(rng: &mut Rng) -> SchedClass {
+    let priority = RtPriority::new(1 + rng.below(10) as u8);
+    match rng.below(3) {
+        0 => SchedClass::Fifo(priority),
+        1 => SchedClass::RoundRobin(priority),
+        _ => SchedClass::Other,
+    }
+}
+
+/// A random reservation of at most `1 / (processes - 1)` of the processor,
+/// so the ones of all the processes but PID 1 fit together.
+fn reservation(rng: &mut Rng, processes: usize) -> Reservation {
+    let runtime = 1 + rng.below(3);
+    let period = runtime * (processes - 1).max(1) + rng.below(10);
+    let deadline = rng.range(&(runtime..=period));
+    Reservation::new(runtime, deadline, period)
+}
+
+/// Turn the generated steps of a process into a workload.
+fn build(nodes: &mut [Node], index: usize) -> Workload {
+    let steps = std::mem::take(&mut nodes[index].steps);
+    let ops = steps
+        .into_iter()
+        .map(|step| match step {
+            Step::Op(op) => op,
+            Step::Fork(child) => {
+                let workload = build(nodes, child);
+                match nodes[child].spawn {
+                    Spawn::Fork => Op::Fork(workload),
+                    Spawn::Class(class) => Op::ForkClass { class, workload },
+                    Spawn::Reserved(reservation) => Op::ForkReserved {
+                        reservation,
+                        workload,
+                    },
+                }
+            }
+        })
+        .collect();
+    Workload::new(nodes[index].priority, ops)
+}
@@ -0,0 +1,438 @@
+//! Checking the invariants of any [`Scheduler`] with random system calls.
+//!
+//! The check plays the role of the processor, without threads: it asks the
+//! scheduler for a decision, stops the running process with a random
+//! system call or an expired timeslice and verifies what the scheduler
+//! reports after every step.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::{self, Display};
+
+use scheduler::{
+    Pid, ProcessState, Reservation, RtPriority, SchedClass, Scheduler, SchedulingDecision,
+    StopReason, Syscall, SyscallResult,
+};
+
+use crate::generator::Rng;
+use crate::ProcessInfo;
+
+/// The maximum number of processes alive at the same time.
+const MAX_PROCESSES: usize = 8;
+
+/// The number of events the processes wait for and signal.
+const EVENTS: usize = 3;
+
+/// The number of process groups the processes move between.
+const GROUPS: usize = 3;
+
+/// An invariant that the scheduler has broken.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    /// The seed of the random system calls.
+    pub seed: u64,
+
+    /// The number of scheduling decisions taken before the violation.
+    pub step: usize,
+
+    /// The broken invariant.
+    pub message: String,
+
+    /// The decisions and the stop reasons that led to the violation.
+    pub history: Vec<String>,
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "seed {}, step {}: {}",
+            self.seed, self.step, self.message
+        )?;
+        for line in self.history.iter() {
+            writeln!(f, "    {line}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Drive the scheduler with random system calls and check that:
+///
+/// * exactly one process, the one scheduled, is running after a [`SchedulingDecision::Run`];
+/// * the PIDs are unique and increasing;
+/// * [`Scheduler::list`] contains every live process and only them;
+/// * [`SchedulingDecision::Sleep`] is returned only when no process is ready;
+/// * [`SchedulingDecision::Deadlock`] is returned only when every process waits for an event;
+/// * [`SchedulingDecision::Done`] is returned only when no process is left;
+/// * the timings of the processes never decrease;
+/// * a [`Syscall::Yield`] is accounted as one time unit of system call and
+///   the rest of the used time as execution, and the process runs again only
+///   after the ready processes with the same priority, group and details.
+///
+/// * `scheduler` - a new scheduler, without processes.
+/// * `seed` - the same seed issues the same system calls.
+/// * `steps` - the maximum number of scheduling decisions.
+///
+/// ## Example
+///
+/// ```rust
+/// use std::num::NonZeroUsize;
+///
+/// let scheduler = scheduler::round_robin(NonZeroUsize::new(3).unwrap(), 1);
+/// assert_eq!(processor::check_invariants(scheduler, 7, 100), Ok(()));
+/// ```
+pub fn check_invariants<S: Scheduler>(
+    scheduler: S,
+    seed: u64,
+    steps: usize,
+) -> Result<(), Violation> {
+    let mut checker = Checker {
+        scheduler,
+        rng: Rng::new(seed),
+        seed,
+        step: 0,
+        history: vec![],
+        live: BTreeSet::new(),
+        last_pid: None,
+        timings: HashMap::new(),
+        yielded: None,
+    };
+    checker.run(steps)
+}
+
+struct Checker<S: Scheduler> {
+    scheduler: S,
+    rng: Rng,
+    seed: u64,
+    step: usize,
+    history: Vec<String>,
+    live: BTreeSet<Pid>,
+    last_pid: Option<Pid>,
+    timings: HashMap<Pid, (usize, usize, usize)>,
+    /// The process that has yielded and the ready processes it has to let
+    /// run before it.
+    yielded: Option<(Pid, Vec<Pid>)>,
+}
+
+impl<S: Scheduler> Checker<S> {
+    fn run(&mut self, steps: usize) -> Result<(), Violation> {
+        let reason = StopReason::Syscall {
+            syscall: Syscall::Fork(0),
+            remaining: 0,
+        };
+        let result = self.scheduler.stop(reason);
+        self.history.push(format!("{reason} -> {result:?}"));
+        self.forked(result)?;
+        if self.last_pid != Some(Pid::new(1)) {
+            return Err(self.violation("the first process does not have PID 1".to_string()));
+        }
+
+        while self.step < steps {
+            self.step += 1;
+            let decision = self.scheduler.next();
+            self.history.push(decision.to_string());
+            let states = self.observe()?;
+            if let Some((yielder, peers)) = self.yielded.take() {
+                if matches!(decision, SchedulingDecision::Run { pid, .. } if pid == yielder)
+                    && !peers.is_empty()
+                {
+                    return Err(self.violation(format!(
+                        "process {yielder} runs again after a yield, before {peers:?}"
+                    )));
+                }
+            }
+
+            match decision {
+                SchedulingDecision::Run { pid, timeslice } => {
+                    let running = states
+                        .iter()
+                        .filter(|(_, state)| *state == ProcessState::Running)
+                        .map(|(pid, _)| *pid)
+                        .collect::<Vec<Pid>>();
+                    if running != [pid] {
+                        return Err(self.violation(format!(
+                            "process {pid} is scheduled, but the running processes are {running:?}"
+                        )));
+                    }
+                    self.stop(pid, timeslice.get(), &states)?;
+                    self.observe()?;
+                }
+                SchedulingDecision::Sleep(_) => {
+                    if states.iter().any(|(_, state)| {
+                        matches!(state, ProcessState::Ready | ProcessState::Running)
+                    }) {
+                        return Err(self.violation("sleep while processes are ready".to_string()));
+                    }
+                    if !states.iter().any(|(_, state)| {
+                        matches!(
+                            state,
+                            ProcessState::Waiting { event: None } | ProcessState::Throttled
+                        )
+                    }) {
+                        return Err(self.violation(
+                            "sleep while no process sleeps or is throttled".to_string(),
+                        ));
+                    }
+                }
+                SchedulingDecision::Deadlock => {
+                    let waiting = states.iter().all(|(_, state)| {
+                        matches!(state, ProcessState::Waiting { event: Some(_) })
+                    });
+                    if states.is_empty() || !waiting {
+                        return Err(self.violation(
+                            "deadlock while not every process waits for an event".to_string(),
+                        ));
+                    }
+                    return Ok(());
+                }
+                SchedulingDecision::Panic(_) => {
+                    if self.live.contains(&Pid::new(1)) || self.live.is_empty() {
+                        return Err(self.violation(
+                            "panic while process 1 is alive or no process is left".to_string(),
+                        ));
+                    }
+                    return Ok(());
+                }
+                SchedulingDecision::Done => {
+                    if !self.live.is_empty() {
+                        return Err(self
+                            .violation(format!("done while processes {:?} are alive", self.live)));
+                    }
+                    return Ok(());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Stop the running process with an expired timeslice or a system call.
+    fn stop(
+        &mut self,
+        pid: Pid,
+        timeslice: usize,
+        states: &[(Pid, ProcessState)],
+    ) -> Result<(), Violation> {
+        // the processor consumes at least one time unit before it stops a process
+        let remaining = self.rng.below(timeslice);
+        let reason = if remaining == 0 && self.rng.chance(50) {
+            StopReason::Expired
+        } else {
+            StopReason::Syscall {
+                syscall: self.syscall(pid, states),
+                remaining,
+            }
+        };
+        let result = self.scheduler.stop(reason);
+        self.history.push(format!("{reason} -> {result:?}"));
+
+        let StopReason::Syscall { syscall, remaining } = reason else {
+            return Ok(());
+        };
+        match syscall {
+            Syscall::Yield => self.yielded(pid, timeslice - remaining),
+            // a reservation that does not fit is denied
+            Syscall::ForkReserved { .. } if result == SyscallResult::AdmissionDenied => Ok(()),
+            Syscall::Fork(_) | Syscall::ForkClass { .. } | Syscall::ForkReserved { .. } => {
+                self.forked(result)
+            }
+            Syscall::Exit => {
+                self.live.remove(&pid);
+                self.timings.remove(&pid);
+                Ok(())
+            }
+            Syscall::Signal(event) | Syscall::SignalOne(event) | Syscall::SignalLatched(event) => {
+                let waiting = states
+                    .iter()
+                    .filter(|(_, state)| *state == ProcessState::Waiting { event: Some(event) })
+                    .count();
+                let limit = if let Syscall::SignalOne(_) = syscall {
+                    waiting.min(1)
+                } else {
+                    waiting
+                };
+                match result {
+                    SyscallResult::Woken(woken) if woken <= limit => Ok(()),
+                    _ => Err(self.violation(format!(
+                        "{syscall:?} returned {result:?} with {waiting} processes waiting"
+                    ))),
+                }
+            }
+            _ => match result {
+                SyscallResult::Pid(_) => {
+                    Err(self.violation(format!("{syscall:?} returned {result:?}")))
+                }
+                _ => Ok(()),
+            },
+        }
+    }
+
+    /// A random system call for the running process.
+    fn syscall(&mut self, pid: Pid, states: &[(Pid, ProcessState)]) -> Syscall {
+        // waiting is likely to deadlock if no other process can signal
+        let runnable = states.iter().any(|(other, state)| {
+            *other != pid
+                && matches!(
+                    state,
+                    ProcessState::Ready
+                        | ProcessState::Waiting { event: None }
+                        | ProcessState::Throttled
+                )
+        });
+        let rng = &mut self.rng;
+        let event = 1 + rng.below(EVENTS);
+        let priority = rng.below(11) as i8 - 5;
+        match rng.below(100) {
+            0..=11 if self.live.len() < MAX_PROCESSES => Syscall::Fork(priority),
+            12..=13 if self.live.len() < MAX_PROCESSES => Syscall::ForkClass {
+                priority,
+                class: class(rng),
+            },
+            14..=16 if self.live.len() < MAX_PROCESSES => Syscall::ForkReserved {
+                priority,
+                reservation: reservation(rng),
+            },
+            0..=29 => Syscall::Sleep(1 + rng.below(5)),
+            30..=37 => Syscall::Io {
+                device: rng.below(2),
+                duration: 1 + rng.below(5),
+            },
+            38..=47 if runnable => Syscall::Wait(event),
+            38..=57 => Syscall::Signal(event),
+            58..=63 => Syscall::SignalOne(event),
+            64..=69 => Syscall::SignalLatched(event),
+            70..=75 => {
+                // a live process or one that does not exist
+                let mut pids = self.live.iter().copied().collect::<Vec<Pid>>();
+                pids.push(self.last_pid.unwrap_or(pid) + 1);
+                let target = pids[rng.below(pids.len())];
+                match rng.below(4) {
+                    0 => Syscall::SetClass(target, class(rng)),
+                    1 => Syscall::SetGroup(target, rng.below(GROUPS)),
+                    _ => Syscall::SetPriority(target, priority),
+                }
+            }
+            76..=93 => Syscall::Yield,
+            // the process with PID 1 rarely exits before the others
+            _ if pid == 1 && self.live.len() > 1 && !rng.chance(10) => Syscall::Yield,
+            _ => Syscall::Exit,
+        }
+    }
+
+    /// Check the accounting of a yield that has used `time` units and
+    /// remember the processes that have to run before the one that yielded.
+    fn yielded(&mut self, pid: Pid, time: usize) -> Result<(), Violation> {
+        let processes = self
+            .scheduler
+            .list()
+            .into_iter()
+            .map(ProcessInfo::snapshot)
+            .collect::<Vec<ProcessInfo>>();
+        let Some(process) = processes.iter().find(|process| process.pid == pid) else {
+            return Err(self.violation(format!("process {pid} is not listed after a yield")));
+        };
+
+        let (_, syscall, execute) = process.timings;
+        let (_, last_syscall, last_execute) = self.timings[&pid];
+        if syscall != last_syscall + 1 || execute != last_execute + time - 1 {
+            return Err(self.violation(format!(
+                "a yield after {time} units changed the system call and execution time \
+                 of process {pid} from {:?} to {:?}",
+                (last_syscall, last_execute),
+                (syscall, execute)
+            )));
+        }
+
+        let peers = processes
+            .iter()
+            .filter(|other| {
+                other.pid != pid
+                    && other.state == ProcessState::Ready
+                    && (other.priority, other.group, &other.ext)
+                        == (process.priority, process.group, &process.ext)
+            })
+            .map(|other| other.pid)
+            .collect();
+        self.yielded = Some((pid, peers));
+        Ok(())
+    }
+
+    /// Record the process created by a fork.
+    fn forked(&mut self, result: SyscallResult) -> Result<(), Violation> {
+        let SyscallResult::Pid(pid) = result else {
+            return Err(self.violation(format!("fork returned {result:?}")));
+        };
+        if self.last_pid.is_some_and(|last| pid <= last) {
+            return Err(self.violation(format!(
+                "fork returned PID {pid} after PID {}",
+                self.last_pid.unwrap()
+            )));
+        }
+        self.last_pid = Some(pid);
+        self.live.insert(pid);
+        Ok(())
+    }
+
+    /// Check the processes listed by the scheduler and return their states.
+    fn observe(&mut self) -> Result<Vec<(Pid, ProcessState)>, Violation> {
+        let mut states = vec![];
+        let mut timings = vec![];
+        for process in self.scheduler.list() {
+            states.push((process.pid(), process.state()));
+            timings.push((process.pid(), process.timings()));
+        }
+
+        let listed = states
+            .iter()
+            .map(|(pid, _)| *pid)
+            .collect::<BTreeSet<Pid>>();
+        if listed.len() != states.len() {
+            return Err(self.violation(format!("duplicate PIDs listed: {states:?}")));
+        }
+        if listed != self.live {
+            return Err(self.violation(format!(
+                "listed processes {listed:?}, but the live processes are {:?}",
+                self.live
+            )));
+        }
+
+        for (pid, (total, syscall, execute)) in timings {
+            if let Some((last_total, last_syscall, last_execute)) = self.timings.get(&pid) {
+                if total < *last_total || syscall < *last_syscall || execute < *last_execute {
+                    return Err(self.violation(format!(
+                        "the timings of process {pid} decreased from {:?} to {:?}",
+                        (last_total, last_syscall, last_execute),
+                        (total, syscall, execute)
+                    )));
+                }
+            }
+            self.timings.insert(pid, (total, syscall, execute));
+        }
+
+        Ok(states)
+    }
+
+    fn violation(&self, message: String) -> Violation {
+        Violation {
+            seed: self.seed,
+            step: self.step,
+            message,
+            history: self.history.clone(),
+        }
+    }
+}
+
+/// A random reservation, that uses at most a third of the processor.
+fn reservation(rng: &mut Rng) -> Reservation {
+    let runtime = 1 + rng.below(3);
+    let deadline = runtime + rng.below(5);
+    Reservation::new(runtime, deadline, 2 * runtime + deadline + rng.below(5))
+}
+
+/// A random scheduling class, normal or real-time.
+fn class(rng: &mut Rng) -> SchedClass {
+    let priority = RtPriority::new(1 + rng.below(99) as u8);
+    match rng.below(3) {
+        0 => SchedClass::Fifo(priority),
+        1 => SchedClass::RoundRobin(priority),
+        _ => SchedClass::Other,
+    }
+}
@@ -9,9 +9,41 @@ use std::sync::{Arc, Condvar, Mutex};
 use std::{mem, thread};
 
 use scheduler::{
-    Pid, ProcessState, Scheduler, SchedulingDecision, StopReason, Syscall, SyscallResult,
+    PanicReason, Pcb, Pid, ProcessState, Reservation, SchedClass, Scheduler, SchedulerError,
+    SchedulingDecision, StopReason, Syscall, SyscallResult, ThrottleEvent,
 };
 
+/// Print a simulation step, only if the `output` feature is enabled.
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        if cfg!(feature = "output") {
+            println!($($arg)*);
+        }
+    };
+}
+
+mod analysis;
+mod deadlock;
+mod device;
+mod generator;
+mod invariants;
+mod metrics;
+mod periodic;
+mod reference;
+mod workload;
+
+pub use analysis::{liu_layland_bound, Analysis, ResponseComparison};
+use deadlock::Owners;
+pub use deadlock::{Blocked, DeadlockReport};
+pub use device::DeviceInfo;
+use device::Devices;
+pub use generator::GeneratorConfig;
+pub use invariants::{check_invariants, Violation};
+pub use metrics::{decimal, DeviceMetrics, GroupMetrics, Metrics, ProcessMetrics};
+pub use periodic::{Job, PeriodicRun, PeriodicTask, TaskMetrics, TaskSet};
+pub use reference::{compare_engines, Divergence, FuzzCase};
+pub use workload::{Op, Workload};
+
 /// Running iteration log
 #[derive(Debug)]
 pub struct Log {
@@ -24,6 +56,32 @@ pub struct Log {
     /// The list of processes and their corresponding states
     /// returned by the scheduler.
     pub processes: HashMap<Pid, ProcessInfo>,
+
+    /// The list of devices that have received requests.
+    pub devices: Vec<DeviceInfo>,
+
+    /// The time spent switching to the process, if the decision runs a
+    /// different process than the previous [`SchedulingDecision::Run`].
+    pub context_switch: Option<usize>,
+
+    /// The scheduler contract violation that has stopped the simulation,
+    /// logged with a [`SchedulingDecision::Panic`].
+    pub error: Option<SchedulerError>,
+
+    /// The blocked processes and what they wait for, logged with a
+    /// [`SchedulingDecision::Deadlock`].
+    pub deadlock: Option<DeadlockReport>,
+
+    /// The process groups that the scheduler has throttled or released
+    /// since the previous decision.
+    pub throttle_events: Vec<ThrottleEvent>,
+
+    /// The processor's time when the scheduler has taken the decision.
+    pub start_time: usize,
+
+    /// The processor's time when the iteration has ended, when the process
+    /// has stopped or the processor has woken up.
+    pub end_time: usize,
 }
 
 impl Log {
@@ -31,18 +89,95 @@ impl Log {
         decision: SchedulingDecision,
         stop_reason: Option<(StopReason, SyscallResult)>,
         processes: HashMap<Pid, ProcessInfo>,
+        devices: Vec<DeviceInfo>,
+        context_switch: Option<usize>,
     ) -> Log {
         Log {
             decision,
             stop_reason,
             processes,
+            devices,
+            context_switch,
+            error: None,
+            deadlock: None,
+            throttle_events: vec![],
+            start_time: 0,
+            end_time: 0,
         }
     }
+
+    /// The amount of time used by the iteration: the context switch and
+    /// the time used by the process, or the time the processor has slept.
+    pub fn elapsed(&self) -> usize {
+        self.context_switch.unwrap_or(0) + self.run_time() + self.idle_time()
+    }
+
+    /// The amount of time the process has used the processor during the iteration.
+    pub fn run_time(&self) -> usize {
+        match (self.decision, self.stop_reason) {
+            (SchedulingDecision::Run { timeslice, .. }, Some((reason, _))) => match reason {
+                StopReason::Syscall { remaining, .. } => timeslice.get().saturating_sub(remaining),
+                StopReason::Expired => timeslice.get(),
+            },
+            _ => 0,
+        }
+    }
+
+    /// The amount of time the processor has slept during the iteration.
+    pub fn idle_time(&self) -> usize {
+        match self.decision {
+            SchedulingDecision::Sleep(time) => time.get(),
+            _ => 0,
+        }
+    }
+
+    /// The processes left alive by a [`SchedulingDecision::Panic`], by PID.
+    pub fn survivors(&self) -> Vec<Pid> {
+        if !matches!(self.decision, SchedulingDecision::Panic(_)) {
+            return vec![];
+        }
+        let mut pids = self.processes.keys().copied().collect::<Vec<Pid>>();
+        pids.sort();
+        pids
+    }
+
+    /// The processes held back until the next period of their group's
+    /// bandwidth, by PID.
+    pub fn throttled(&self) -> Vec<Pid> {
+        let mut pids = self
+            .processes
+            .values()
+            .filter(|process| process.state == ProcessState::Throttled)
+            .map(|process| process.pid)
+            .collect::<Vec<Pid>>();
+        pids.sort();
+        pids
+    }
 }
 
 impl Display for Log {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "{}", self.decision).unwrap();
+        if let Some(error) = self.error {
+            writeln!(f, "Error: {}", error).unwrap();
+        }
+        if let Some(deadlock) = &self.deadlock {
+            write!(f, "{}", deadlock).unwrap();
+        }
+        let survivors = self.survivors();
+        if !survivors.is_empty() {
+            let pids = survivors
+                .iter()
+                .map(Pid::to_string)
+                .collect::<Vec<String>>();
+            writeln!(f, "Surviving processes: {}", pids.join(", ")).unwrap();
+        }
+        for event in self.throttle_events.iter() {
+            writeln!(f, "{}", event).unwrap();
+        }
+        if let Some(time) = self.context_switch.filter(|time| *time > 0) {
+            writeln!(f, "Context switch for {} slices", time).unwrap();
+        }
         // writeln!(f, "===== Processes =====");
         writeln!(f, "PID\tSTATE\t\tPRI\tTOTAL\tSYSCALL\tEXECUTE\tEXTRA").unwrap();
         let mut pids = self.processes.keys().collect::<Vec<&Pid>>();
@@ -50,6 +185,12 @@ impl Display for Log {
         for pid in pids.into_iter() {
             writeln!(f, "{}", self.processes.get(pid).unwrap()).unwrap();
         }
+        if !self.devices.is_empty() {
+            writeln!(f, "DEVICE\tREQS\tBUSY\tQUEUE").unwrap();
+            for device in self.devices.iter() {
+                writeln!(f, "{}", device).unwrap();
+            }
+        }
         if let Some(log) = self.stop_reason {
             writeln!(f, "{} -> {:?}", log.0, (log.1)).unwrap();
         }
@@ -62,67 +203,38 @@ impl PartialEq<Log> for Log {
         self.decision == other.decision
             && self.stop_reason == other.stop_reason
             && self.processes == other.processes
+            && self.devices == other.devices
+            && self.context_switch == other.context_switch
+            && self.error == other.error
+            && self.deadlock == other.deadlock
+            && self.throttle_events == other.throttle_events
+            && self.start_time == other.start_time
+            && self.end_time == other.end_time
     }
 }
 
-/// Information about a process state.
-#[derive(Debug, PartialEq)]
-pub struct ProcessInfo {
-    /// The PID of the process.
-    pub pid: Pid,
+/// Information about a process state, copied from the scheduler.
+pub type ProcessInfo = Pcb<String>;
 
-    /// The process state.
-    pub state: ProcessState,
-
-    /// The process timings (total time, system call time, running time).
-    pub timings: (usize, usize, usize),
-
-    /// The process priority
-    pub priority: i8,
-
-    /// Extra details about the process
-    pub extra: String,
-}
-
-impl ProcessInfo {
-    fn new(
-        pid: Pid,
-        state: ProcessState,
-        timings: (usize, usize, usize),
-        priority: i8,
-        extra: String,
-    ) -> ProcessInfo {
-        ProcessInfo {
-            pid,
-            state,
-            timings,
-            priority,
-            extra,
-        }
-    }
-}
-
-impl Display for ProcessInfo {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}\t{}\t\t{}\t{}\t{}\t{}\t{}",
-            self.pid,
-            self.state,
-            self.priority,
-            self.timings.0,
-            self.timings.1,
-            self.timings.2,
-            self.extra
-        )
-    }
+/// The simulation options of the [`Processor`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Options {
+    /// The amount of time consumed every time the processor runs
+    /// a different process than the previously running one.
+    pub context_switch: usize,
 }
 
 /// The processor simulator.
 pub struct Processor<S: Scheduler + 'static> {
+    options: Options,
     scheduler: Arc<Mutex<S>>,
     current_process: Arc<(Mutex<Option<Pid>>, Condvar)>,
     remaining: AtomicUsize,
+    timeslice: AtomicUsize,
+    clock: AtomicUsize,
+    devices: Mutex<Devices>,
+    last_run: Mutex<Option<Pid>>,
+    owners: Mutex<Owners>,
     logs: Mutex<Vec<Log>>,
     running: AtomicBool,
 }
@@ -132,7 +244,7 @@ impl<S: Scheduler + 'static> Processor<S> {
     ///
     /// * `scheduler` - the scheduler to use for the simulation.
     /// * `f` - a function with the instructions for the process with
-    ///         PID 1.
+    ///   PID 1.
     ///
     /// ## Example
     ///
@@ -159,23 +271,61 @@ impl<S: Scheduler + 'static> Processor<S> {
     /// });
     /// ```
     pub fn run<F>(scheduler: S, f: F) -> Vec<Log>
+    where
+        F: FnOnce(&Process<S>) + Send,
+    {
+        Processor::run_with_options(scheduler, Options::default(), f)
+    }
+
+    /// Start a new processor simulation with the given options.
+    ///
+    /// * `scheduler` - the scheduler to use for the simulation.
+    /// * `options` - the simulation options.
+    /// * `f` - a function with the instructions for the process with
+    ///   PID 1.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use processor::{Options, Processor};
+    /// use std::num::NonZeroUsize;
+    ///
+    /// let options = Options { context_switch: 1 };
+    /// Processor::run_with_options(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), options, |process| {
+    ///     process.fork(|process| process.exec(), 0);
+    ///     process.exec();
+    /// });
+    /// ```
+    pub fn run_with_options<F>(scheduler: S, options: Options, f: F) -> Vec<Log>
     where
         F: FnOnce(&Process<S>) + Send,
     {
         let processor = Arc::new(Processor {
+            options,
             scheduler: Arc::new(Mutex::new(scheduler)),
             current_process: Arc::new((Mutex::new(None), Condvar::new())),
             remaining: AtomicUsize::new(1),
+            timeslice: AtomicUsize::new(0),
+            clock: AtomicUsize::new(0),
+            devices: Mutex::new(Devices::default()),
+            last_run: Mutex::new(None),
+            owners: Mutex::new(Owners::default()),
             logs: Mutex::new(vec![]),
             running: AtomicBool::new(true),
         });
 
-        let SyscallResult::Pid(pid) = processor.scheduler(StopReason::syscall(Syscall::Fork(0))) else {
-            panic!("Fork did not return a pid");
-        };
-
-        if pid != 1 {
-            panic!("Scheduler did not return PID 1 for the first process");
+        let reason = StopReason::syscall(Syscall::Fork(0));
+        let result = processor.scheduler(reason);
+        let pid = Pid::new(1);
+        if result != SyscallResult::Pid(pid) {
+            if processor.is_running() {
+                let mut scheduler = processor.scheduler.lock().unwrap();
+                processor.fail(
+                    &mut *scheduler,
+                    SchedulerError::UnexpectedResult { reason, result },
+                );
+            }
+            return processor.get_logs();
         }
 
         let mutex = processor.current_process.clone();
@@ -189,6 +339,8 @@ impl<S: Scheduler + 'static> Processor<S> {
                 process.suspend();
                 f(&process);
                 process.exit();
+                // orphans can outlive process 1, wait for the simulation to stop
+                process.suspend();
                 process.processor.get_logs()
             })
             .join()
@@ -205,11 +357,41 @@ impl<S: Scheduler + 'static> Processor<S> {
         }
     }
 
+    /// The processor's time, with the time units used by the running process.
+    fn now(&self) -> usize {
+        let used = self
+            .timeslice
+            .load(Ordering::Relaxed)
+            .saturating_sub(self.remaining.load(Ordering::Relaxed));
+        self.clock.load(Ordering::Relaxed) + used
+    }
+
     fn scheduler(&self, mut reason: StopReason) -> SyscallResult {
         if self.is_running() {
             self.remaining.fetch_sub(1, Ordering::Relaxed);
             let mut scheduler = self.scheduler.lock().unwrap();
             reason.set_remaining(self.remaining.load(Ordering::Relaxed));
+            self.advance(match reason {
+                StopReason::Syscall { remaining, .. } => self
+                    .timeslice
+                    .load(Ordering::Relaxed)
+                    .saturating_sub(remaining),
+                StopReason::Expired => self.timeslice.load(Ordering::Relaxed),
+            });
+
+            // the device serves the request after the ones queued before it
+            let completion = match reason {
+                StopReason::Syscall {
+                    syscall: Syscall::Io { device, duration },
+                    ..
+                } => {
+                    let pid = self.current_process.0.lock().unwrap().unwrap();
+                    let wakeup = self.devices.lock().unwrap().request(device, pid, duration);
+                    Some((pid, wakeup))
+                }
+                _ => None,
+            };
+
             let result = scheduler.stop(reason);
             {
                 let mut logs = self.logs.lock().unwrap();
@@ -221,44 +403,61 @@ impl<S: Scheduler + 'static> Processor<S> {
                 }
             }
 
+            if let Err(error) = contract(reason, result) {
+                self.fail(&mut *scheduler, error);
+                return result;
+            }
+
+            // the scheduler blocks the process until the device completes the request
+            if let Some((pid, wakeup)) = completion {
+                scheduler.io_completion(pid, wakeup);
+            }
+
             let mut current_process = self.current_process.0.lock().unwrap();
             *current_process = None;
             while self.is_running() && current_process.is_none() {
                 let next = scheduler.next();
-                let mut process_map = HashMap::new();
-                for process in scheduler.list() {
-                    process_map.insert(
-                        process.pid(),
-                        ProcessInfo::new(
-                            process.pid(),
-                            process.state(),
-                            process.timings(),
-                            process.priority(),
-                            process.extra(),
-                        ),
-                    );
+                let process_map = process_map(&mut *scheduler);
+                let context_switch = self.context_switch(next);
+                if let Some(time) = context_switch {
+                    scheduler.context_switch(time);
+                }
+                let devices = self.devices.lock().unwrap().list();
+                let mut log = Log::new(next, None, process_map, devices, context_switch);
+                log.throttle_events = scheduler.throttle_events();
+                if next == SchedulingDecision::Deadlock {
+                    let owners = self.owners.lock().unwrap();
+                    log.deadlock = Some(DeadlockReport::new(&log.processes, |event| {
+                        owners.signalers(event)
+                    }));
                 }
-                (*self.logs.lock().unwrap()).push(Log::new(next, None, process_map));
+                push_log(
+                    &mut self.logs.lock().unwrap(),
+                    log,
+                    self.clock.load(Ordering::Relaxed),
+                );
                 // println!("{}", next);
                 match next {
                     SchedulingDecision::Run { pid, timeslice } => {
                         self.remaining.store(timeslice.into(), Ordering::Relaxed);
+                        self.timeslice.store(timeslice.into(), Ordering::Relaxed);
                         *current_process = Some(pid);
                         self.current_process.1.notify_all();
                     }
                     SchedulingDecision::Sleep(time) => {
-                        println!("SLEEP {time}");
+                        trace!("SLEEP {time}");
+                        self.advance(time.get());
                     }
                     SchedulingDecision::Deadlock => {
-                        println!("DEADLOCK");
+                        trace!("DEADLOCK");
                         self.stop();
                     }
-                    SchedulingDecision::Panic => {
-                        println!("PANIC");
+                    SchedulingDecision::Panic(reason) => {
+                        trace!("PANIC {reason}");
                         self.stop();
                     }
                     SchedulingDecision::Done => {
-                        println!("DONE");
+                        trace!("DONE");
                         self.stop();
                     }
                 }
@@ -269,6 +468,48 @@ impl<S: Scheduler + 'static> Processor<S> {
         }
     }
 
+    /// Stop the simulation with a [`SchedulingDecision::Panic`] log that
+    /// describes the scheduler contract violation.
+    fn fail(&self, scheduler: &mut S, error: SchedulerError) {
+        trace!("ERROR {error}");
+        let mut log = Log::new(
+            SchedulingDecision::Panic(PanicReason::ContractViolation),
+            None,
+            process_map(scheduler),
+            self.devices.lock().unwrap().list(),
+            None,
+        );
+        log.error = Some(error);
+        push_log(
+            &mut self.logs.lock().unwrap(),
+            log,
+            self.clock.load(Ordering::Relaxed),
+        );
+        self.stop();
+    }
+
+    /// Consume the context switch time if the decision runs another process
+    /// than the previous [`SchedulingDecision::Run`] and return it.
+    fn context_switch(&self, decision: SchedulingDecision) -> Option<usize> {
+        let SchedulingDecision::Run { pid, .. } = decision else {
+            return None;
+        };
+
+        let previous = self.last_run.lock().unwrap().replace(pid);
+        if previous.is_some_and(|previous| previous != pid) {
+            self.advance(self.options.context_switch);
+            Some(self.options.context_switch)
+        } else {
+            None
+        }
+    }
+
+    /// Advance the processor's time, completing the finished device requests.
+    fn advance(&self, time: usize) {
+        let now = self.clock.fetch_add(time, Ordering::Relaxed) + time;
+        self.devices.lock().unwrap().advance(now);
+    }
+
     fn get_logs(&self) -> Vec<Log> {
         let mut logs = self.logs.lock().unwrap();
         let mut res = vec![];
@@ -302,30 +543,93 @@ impl<S: Scheduler + 'static> Process<S> {
             wait = self.mutex.1.wait(wait).unwrap();
         }
         if self.processor.is_running() {
-            println!("RUNNING {}", self.pid);
+            trace!("RUNNING {}", self.pid);
         }
     }
 
     /// Execute one unit of time.
     pub fn exec(&self) {
-        println!("{}: EXEC", self.pid);
+        trace!("{}: EXEC", self.pid);
         if !self.processor.exec() {
-            println!("PREEMPTED {}", self.pid);
+            trace!("PREEMPTED {}", self.pid);
             self.processor.scheduler(StopReason::expired());
             self.suspend();
         }
     }
 
+    /// The processor's time, including the time units that the process has
+    /// executed since it was scheduled. It is not a system call and does not
+    /// use any time.
+    pub fn now(&self) -> usize {
+        self.processor.now()
+    }
+
     /// Send a [`Syscall::Fork`] system call.
+    ///
+    /// Panics if the scheduler does not return a PID, use [`Process::try_fork`]
+    /// if the simulation may stop before the process is created.
     pub fn fork<F>(&self, f: F, priority: i8) -> Pid
     where
         F: FnOnce(&Process<S>) + Send + 'static,
     {
-        let SyscallResult::Pid(pid) = self.processor.scheduler(StopReason::syscall(Syscall::Fork(priority))) else {
+        let Some(pid) = self.try_fork(f, priority) else {
             panic!("Fork did not return a pid");
         };
+        pid
+    }
+
+    /// Send a [`Syscall::Fork`] system call.
+    ///
+    /// Returns the PID of the new process, or `None` if the simulation
+    /// has stopped and the process was not created.
+    pub fn try_fork<F>(&self, f: F, priority: i8) -> Option<Pid>
+    where
+        F: FnOnce(&Process<S>) + Send + 'static,
+    {
+        self.spawn(f, Syscall::Fork(priority))
+    }
+
+    /// Send a [`Syscall::ForkClass`] system call, that creates the process
+    /// in a scheduling class.
+    ///
+    /// Returns the PID of the new process, or `None` if the simulation
+    /// has stopped and the process was not created.
+    pub fn fork_class<F>(&self, f: F, priority: i8, class: SchedClass) -> Option<Pid>
+    where
+        F: FnOnce(&Process<S>) + Send + 'static,
+    {
+        self.spawn(f, Syscall::ForkClass { priority, class })
+    }
+
+    /// Send a [`Syscall::ForkReserved`] system call, that creates the process
+    /// with a processor reservation.
+    ///
+    /// Returns the PID of the new process, or `None` if the scheduler has
+    /// denied the reservation or the simulation has stopped.
+    pub fn fork_reserved<F>(&self, f: F, priority: i8, reservation: Reservation) -> Option<Pid>
+    where
+        F: FnOnce(&Process<S>) + Send + 'static,
+    {
+        self.spawn(
+            f,
+            Syscall::ForkReserved {
+                priority,
+                reservation,
+            },
+        )
+    }
+
+    fn spawn<F>(&self, f: F, syscall: Syscall) -> Option<Pid>
+    where
+        F: FnOnce(&Process<S>) + Send + 'static,
+    {
+        let SyscallResult::Pid(pid) = self.processor.scheduler(StopReason::syscall(syscall)) else {
+            // the process goes on without a child
+            self.suspend();
+            return None;
+        };
 
-        println!("{}: FORK {}", self.pid, pid);
+        trace!("{}: FORK {}", self.pid, pid);
 
         let mutex = self.mutex.clone();
         let processor = self.processor.clone();
@@ -341,45 +645,226 @@ impl<S: Scheduler + 'static> Process<S> {
             process.exit();
         });
         self.suspend();
-        pid
+        Some(pid)
+    }
+
+    /// Declare that the process will signal the event, so that a deadlock
+    /// report shows it as the process that the waiters wait for. Every
+    /// signal of the event cancels one declaration. It is not a system
+    /// call and does not use any time.
+    ///
+    /// * `event` - the event number that the process will signal.
+    pub fn will_signal(&self, event: usize) {
+        self.processor
+            .owners
+            .lock()
+            .unwrap()
+            .declare(self.pid, event);
     }
 
     /// Send a [`Syscall::Wait`] system call.
     ///
     /// * `event` - the event number to wait for.
     pub fn wait(&self, event: usize) {
-        println!("{}: WAIT {}", self.pid, event);
+        trace!("{}: WAIT {}", self.pid, event);
         self.processor
             .scheduler(StopReason::syscall(Syscall::Wait(event)));
         self.suspend();
     }
 
-    /// Send a [`Syscall::Signal`] system call.
+    /// Send a [`Syscall::Signal`] system call and return the number
+    /// of processes that were woken up.
     ///
     /// * `event` - the event number to signal.
-    pub fn signal(&self, event: usize) {
-        println!("{}: SIGNAL {}", self.pid, event);
+    pub fn signal(&self, event: usize) -> usize {
+        trace!("{}: SIGNAL {}", self.pid, event);
         self.processor
+            .owners
+            .lock()
+            .unwrap()
+            .signaled(self.pid, event);
+        let result = self
+            .processor
             .scheduler(StopReason::syscall(Syscall::Signal(event)));
         self.suspend();
+        woken(result)
+    }
+
+    /// Send a [`Syscall::SignalOne`] system call and return the number
+    /// of processes that were woken up.
+    ///
+    /// * `event` - the event number to signal.
+    pub fn signal_one(&self, event: usize) -> usize {
+        trace!("{}: SIGNAL ONE {}", self.pid, event);
+        self.processor
+            .owners
+            .lock()
+            .unwrap()
+            .signaled(self.pid, event);
+        let result = self
+            .processor
+            .scheduler(StopReason::syscall(Syscall::SignalOne(event)));
+        self.suspend();
+        woken(result)
+    }
+
+    /// Send a [`Syscall::SignalLatched`] system call and return the number
+    /// of processes that were woken up.
+    ///
+    /// * `event` - the event number to signal.
+    pub fn signal_latched(&self, event: usize) -> usize {
+        trace!("{}: SIGNAL LATCHED {}", self.pid, event);
+        self.processor
+            .owners
+            .lock()
+            .unwrap()
+            .signaled(self.pid, event);
+        let result = self
+            .processor
+            .scheduler(StopReason::syscall(Syscall::SignalLatched(event)));
+        self.suspend();
+        woken(result)
     }
 
     /// Send a [`Syscall::Sleep`] system call.
     ///
     /// * `timeslice` - the amout of time to sleep.
     pub fn sleep(&self, timeslice: usize) {
-        println!("{}: SLEEP {}", self.pid, timeslice);
+        trace!("{}: SLEEP {}", self.pid, timeslice);
         self.processor
             .scheduler(StopReason::syscall(Syscall::Sleep(timeslice)));
         self.suspend();
     }
 
+    /// Send a [`Syscall::Io`] system call.
+    ///
+    /// * `device` - the device that serves the request.
+    /// * `duration` - the amount of time the device needs to serve the request.
+    pub fn io(&self, device: usize, duration: usize) {
+        trace!("{}: IO {} {}", self.pid, device, duration);
+        self.processor
+            .scheduler(StopReason::syscall(Syscall::Io { device, duration }));
+        self.suspend();
+    }
+
+    /// Send a [`Syscall::SetPriority`] system call.
+    ///
+    /// * `pid` - the process to change, either this process or one of its children.
+    /// * `priority` - the new priority.
+    pub fn set_priority(&self, pid: Pid, priority: i8) -> SyscallResult {
+        trace!("{}: SET PRIORITY {} {}", self.pid, pid, priority);
+        let result = self
+            .processor
+            .scheduler(StopReason::syscall(Syscall::SetPriority(pid, priority)));
+        self.suspend();
+        result
+    }
+
+    /// Send a [`Syscall::SetClass`] system call.
+    ///
+    /// * `pid` - the process to change, either this process or one of its children.
+    /// * `class` - the new scheduling class.
+    pub fn set_class(&self, pid: Pid, class: SchedClass) -> SyscallResult {
+        trace!("{}: SET CLASS {} {}", self.pid, pid, class);
+        let result = self
+            .processor
+            .scheduler(StopReason::syscall(Syscall::SetClass(pid, class)));
+        self.suspend();
+        result
+    }
+
+    /// Send a [`Syscall::SetGroup`] system call.
+    ///
+    /// * `pid` - the process to move, either this process or one of its children.
+    /// * `group` - the new process group.
+    pub fn set_group(&self, pid: Pid, group: usize) -> SyscallResult {
+        trace!("{}: SET GROUP {} {}", self.pid, pid, group);
+        let result = self
+            .processor
+            .scheduler(StopReason::syscall(Syscall::SetGroup(pid, group)));
+        self.suspend();
+        result
+    }
+
+    /// Change the priority of this process.
+    ///
+    /// * `priority` - the new priority.
+    pub fn nice(&self, priority: i8) -> SyscallResult {
+        self.set_priority(self.pid, priority)
+    }
+
+    /// Send a [`Syscall::Yield`] system call.
+    pub fn yield_now(&self) {
+        trace!("{}: YIELD", self.pid);
+        self.processor
+            .scheduler(StopReason::syscall(Syscall::Yield));
+        self.suspend();
+    }
+
     fn exit(&self) {
-        println!("{}: EXIT", self.pid);
+        trace!("{}: EXIT", self.pid);
+        self.processor.owners.lock().unwrap().exited(self.pid);
         self.processor.scheduler(StopReason::syscall(Syscall::Exit));
     }
 }
 
+/// Add the log of a decision taken at time `now`, after its context switch,
+/// which ends the previous iteration.
+pub(crate) fn push_log(logs: &mut Vec<Log>, mut log: Log, now: usize) {
+    log.start_time = now - log.context_switch.unwrap_or(0);
+    log.end_time = now;
+    if let Some(last) = logs.last_mut() {
+        last.end_time = log.start_time;
+    }
+    logs.push(log);
+}
+
+/// The processes listed by the scheduler.
+pub(crate) fn process_map<S: Scheduler + ?Sized>(scheduler: &mut S) -> HashMap<Pid, ProcessInfo> {
+    let mut process_map = HashMap::new();
+    for process in scheduler.list() {
+        process_map.insert(process.pid(), ProcessInfo::snapshot(process));
+    }
+    process_map
+}
+
+/// Check that the result returned by [`Scheduler::stop`] fits the reason:
+/// only a fork returns a PID, only a reservation can be denied and the
+/// stopped process was running.
+pub(crate) fn contract(reason: StopReason, result: SyscallResult) -> Result<(), SchedulerError> {
+    let fork = matches!(
+        reason,
+        StopReason::Syscall {
+            syscall: Syscall::Fork(_) | Syscall::ForkClass { .. } | Syscall::ForkReserved { .. },
+            ..
+        }
+    );
+    let reserved = matches!(
+        reason,
+        StopReason::Syscall {
+            syscall: Syscall::ForkReserved { .. },
+            ..
+        }
+    );
+    match result {
+        SyscallResult::NoRunningProcess => Err(SchedulerError::UnexpectedResult { reason, result }),
+        SyscallResult::Pid(_) if !fork => Err(SchedulerError::UnexpectedResult { reason, result }),
+        SyscallResult::AdmissionDenied if reserved => Ok(()),
+        _ if fork && !matches!(result, SyscallResult::Pid(_)) => {
+            Err(SchedulerError::UnexpectedResult { reason, result })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Extract the number of woken processes from a signal's [`SyscallResult`].
+fn woken(result: SyscallResult) -> usize {
+    match result {
+        SyscallResult::Woken(count) => count,
+        _ => 0,
+    }
+}
+
 /// Format the [`Processor`]'s logs to a [`String`].
 ///
 /// * `logs` - the logs returned by the [`Processor`].
@@ -402,7 +887,13 @@ pub fn format_logs(logs: &[Log]) -> String {
     for (iteration, log) in logs.iter().enumerate() {
         fmt::write(
             &mut s,
-            format_args!("===== Iteration: {} =====\n{}\n", iteration + 1, log),
+            format_args!(
+                "===== Iteration: {} =====\nTime: {} - {}\n{}\n",
+                iteration + 1,
+                log.start_time,
+                log.end_time,
+                log
+            ),
         )
         .unwrap();
     }
@@ -0,0 +1,334 @@
+//! Statistics about a simulation, computed from the [`Processor`](crate::Processor)'s logs.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+
+use scheduler::{Pid, ProcessState, SchedulingDecision, StopReason, Syscall};
+
+use crate::Log;
+
+/// Statistics about a process.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessMetrics {
+    /// The PID of the process.
+    pub pid: Pid,
+
+    /// The time when the process was created.
+    pub arrival: usize,
+
+    /// The time when the process was scheduled for the first time.
+    pub first_run: Option<usize>,
+
+    /// The time when the process exited.
+    pub completion: Option<usize>,
+
+    /// The amount of time the process has used the processor.
+    pub cpu_time: usize,
+
+    /// The amount of time the process was ready, waiting to be scheduled.
+    pub waiting_time: usize,
+
+    /// The longest time the process was ready without being scheduled.
+    pub longest_wait: usize,
+}
+
+impl ProcessMetrics {
+    /// The time from the creation of the process until it exited.
+    pub fn turnaround(&self) -> Option<usize> {
+        self.completion.map(|completion| completion - self.arrival)
+    }
+
+    /// The time from the creation of the process until it was first scheduled.
+    pub fn response(&self) -> Option<usize> {
+        self.first_run.map(|first_run| first_run - self.arrival)
+    }
+}
+
+/// Statistics about a device.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceMetrics {
+    /// The device number.
+    pub device: usize,
+
+    /// The number of requests the device has received.
+    pub requests: usize,
+
+    /// The amount of time the device has spent serving requests.
+    pub busy_time: usize,
+
+    /// The fraction of the simulation time the device was busy.
+    pub utilisation: f64,
+}
+
+/// Statistics about a process group.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupMetrics {
+    /// The group number.
+    pub group: usize,
+
+    /// The amount of time the processes of the group have used the processor.
+    pub cpu_time: usize,
+
+    /// The fraction of the processor time used by the group.
+    pub share: f64,
+}
+
+/// Statistics about a simulation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Metrics {
+    /// The total amount of simulated time.
+    pub time: usize,
+
+    /// The amount of time the processes have used the processor.
+    pub cpu_time: usize,
+
+    /// The amount of time the processor has been sleeping.
+    pub idle_time: usize,
+
+    /// The number of times the processor has switched to another process.
+    pub context_switches: usize,
+
+    /// The amount of time spent switching between processes.
+    pub context_switch_time: usize,
+
+    /// The statistics of every process, sorted by PID.
+    pub processes: Vec<ProcessMetrics>,
+
+    /// The statistics of every device used during the simulation.
+    pub devices: Vec<DeviceMetrics>,
+
+    /// The statistics of every process group, sorted by group number.
+    pub groups: Vec<GroupMetrics>,
+}
+
+impl Metrics {
+    /// Compute the statistics of a simulation.
+    ///
+    /// * `logs` - the logs returned by the [`Processor`](crate::Processor).
+    pub fn from_logs(logs: &[Log]) -> Metrics {
+        let cpu_time = logs.iter().map(Log::run_time).sum();
+        let idle_time = logs.iter().map(Log::idle_time).sum();
+        let context_switches = logs
+            .iter()
+            .filter(|log| log.context_switch.is_some())
+            .count();
+        let context_switch_time = logs.iter().filter_map(|log| log.context_switch).sum();
+        let time = cpu_time + idle_time + context_switch_time;
+        let processes = processes(logs);
+
+        let devices = logs
+            .last()
+            .map(|log| {
+                log.devices
+                    .iter()
+                    .map(|device| DeviceMetrics {
+                        device: device.device,
+                        requests: device.requests,
+                        busy_time: device.busy_time,
+                        utilisation: ratio(device.busy_time, time),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let groups = groups(logs)
+            .into_iter()
+            .map(|(group, group_time)| GroupMetrics {
+                group,
+                cpu_time: group_time,
+                share: ratio(group_time, cpu_time),
+            })
+            .collect();
+
+        Metrics {
+            time,
+            cpu_time,
+            idle_time,
+            context_switches,
+            context_switch_time,
+            processes,
+            devices,
+            groups,
+        }
+    }
+
+    /// The average turnaround time of the processes that exited.
+    pub fn average_turnaround(&self) -> Option<f64> {
+        average(self.processes.iter().filter_map(ProcessMetrics::turnaround))
+    }
+
+    /// The average waiting time of the processes that exited.
+    pub fn average_waiting(&self) -> Option<f64> {
+        average(
+            self.processes
+                .iter()
+                .filter(|process| process.completion.is_some())
+                .map(|process| process.waiting_time),
+        )
+    }
+
+    /// The average response time of the processes that were scheduled.
+    pub fn average_response(&self) -> Option<f64> {
+        average(self.processes.iter().filter_map(ProcessMetrics::response))
+    }
+
+    /// The processes that were ready for more than `threshold` time units
+    /// without being scheduled.
+    pub fn starving(&self, threshold: usize) -> Vec<Pid> {
+        self.processes
+            .iter()
+            .filter(|process| process.longest_wait > threshold)
+            .map(|process| process.pid)
+            .collect()
+    }
+}
+
+impl Display for Metrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Time: {}", self.time)?;
+        writeln!(f, "CPU time: {}", self.cpu_time)?;
+        writeln!(f, "Idle time: {}", self.idle_time)?;
+        writeln!(
+            f,
+            "Context switches: {} ({} time units)",
+            self.context_switches, self.context_switch_time
+        )?;
+        writeln!(
+            f,
+            "Average turnaround: {}",
+            decimal(self.average_turnaround())
+        )?;
+        writeln!(f, "Average waiting: {}", decimal(self.average_waiting()))?;
+        writeln!(f, "Average response: {}", decimal(self.average_response()))?;
+        writeln!(f, "PID\tARRIVAL\tFIRST\tEXIT\tCPU\tWAITING\tLONGEST")?;
+        for process in self.processes.iter() {
+            writeln!(
+                f,
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                process.pid,
+                process.arrival,
+                optional(process.first_run),
+                optional(process.completion),
+                process.cpu_time,
+                process.waiting_time,
+                process.longest_wait
+            )?;
+        }
+        if !self.devices.is_empty() {
+            writeln!(f, "DEVICE\tREQS\tBUSY\tUTILISATION")?;
+            for device in self.devices.iter() {
+                writeln!(
+                    f,
+                    "{}\t{}\t{}\t{:.2}",
+                    device.device, device.requests, device.busy_time, device.utilisation
+                )?;
+            }
+        }
+        // only the schedulers with groups move processes out of group 0
+        if self.groups.iter().any(|group| group.group != 0) {
+            writeln!(f, "GROUP\tCPU\tSHARE")?;
+            for group in self.groups.iter() {
+                writeln!(f, "{}\t{}\t{:.2}", group.group, group.cpu_time, group.share)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Follow every process through the logs.
+fn processes(logs: &[Log]) -> Vec<ProcessMetrics> {
+    let mut processes = BTreeMap::new();
+    // the time every process has been ready since it was last scheduled
+    let mut waits = BTreeMap::new();
+
+    for log in logs.iter() {
+        let start = log.start_time;
+
+        for (pid, info) in log.processes.iter() {
+            let process = processes.entry(*pid).or_insert(ProcessMetrics {
+                pid: *pid,
+                arrival: start,
+                first_run: None,
+                completion: None,
+                cpu_time: 0,
+                waiting_time: 0,
+                longest_wait: 0,
+            });
+            if info.state == ProcessState::Ready {
+                process.waiting_time += log.elapsed();
+                let wait = waits.entry(*pid).or_insert(0);
+                *wait += log.elapsed();
+                process.longest_wait = process.longest_wait.max(*wait);
+            }
+        }
+
+        if let SchedulingDecision::Run { pid, .. } = log.decision {
+            waits.remove(&pid);
+            if let Some(process) = processes.get_mut(&pid) {
+                process
+                    .first_run
+                    .get_or_insert(start + log.context_switch.unwrap_or(0));
+                process.cpu_time += log.run_time();
+                if let Some((
+                    StopReason::Syscall {
+                        syscall: Syscall::Exit,
+                        ..
+                    },
+                    _,
+                )) = log.stop_reason
+                {
+                    process.completion = Some(log.end_time);
+                }
+            }
+        }
+    }
+
+    processes.into_values().collect()
+}
+
+/// The processor time used by every group that had processes, the time
+/// of a run is charged to the group of the process when it was scheduled.
+fn groups(logs: &[Log]) -> BTreeMap<usize, usize> {
+    let mut groups = BTreeMap::new();
+    for log in logs.iter() {
+        for process in log.processes.values() {
+            groups.entry(process.group).or_insert(0);
+        }
+        if let SchedulingDecision::Run { pid, .. } = log.decision {
+            if let Some(process) = log.processes.get(&pid) {
+                *groups.entry(process.group).or_insert(0) += log.run_time();
+            }
+        }
+    }
+    groups
+}
+
+fn average<I: Iterator<Item = usize>>(values: I) -> Option<f64> {
+    let (sum, count) = values.fold((0, 0), |(sum, count), value| (sum + value, count + 1));
+    if count == 0 {
+        None
+    } else {
+        Some(sum as f64 / count as f64)
+    }
+}
+
+/// Display a missing value as `-`.
+fn optional<T: Display>(value: Option<T>) -> String {
+    match value {
+        Some(value) => value.to_string(),
+        None => "-".to_string(),
+    }
+}
+
+/// Display a missing value as `-` and a value with two decimals.
+pub fn decimal(value: Option<f64>) -> String {
+    optional(value.map(|value| format!("{value:.2}")))
+}
+
+fn ratio(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64
+    }
+}
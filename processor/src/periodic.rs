@@ -0,0 +1,214 @@
+//! Periodic tasks, that release a job every period and have to complete it
+//! before a deadline.
+//!
+//! A [`TaskSet`] is simulated like a [`Workload`](crate::Workload): the
+//! process with PID 1 forks every task with a
+//! [`Syscall::ForkReserved`](scheduler::Syscall::ForkReserved) and
+//! waits for them, so the same task set can be compared between schedulers.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use scheduler::{Pid, Reservation, Scheduler};
+
+use crate::{Log, Options, Processor};
+
+/// The event that the last task signals to the process with PID 1.
+const DONE: usize = 0;
+
+/// A task that releases a job every `period` time units, from the time it is
+/// forked.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PeriodicTask {
+    /// The runtime, the relative deadline and the period that the task
+    /// declares.
+    pub reservation: Reservation,
+
+    /// The processor time every job uses, including the system call that
+    /// waits for the next job. A task that uses more than the runtime it has
+    /// declared overruns its reservation.
+    pub execution: usize,
+}
+
+impl PeriodicTask {
+    /// A task whose jobs use the runtime it declares.
+    pub fn new(reservation: Reservation) -> PeriodicTask {
+        PeriodicTask {
+            reservation,
+            execution: reservation.runtime.get(),
+        }
+    }
+
+    /// A task whose jobs use `execution` time units, panics if it is 0.
+    pub fn overrunning(reservation: Reservation, execution: usize) -> PeriodicTask {
+        assert!(execution > 0, "a job uses at least one time unit");
+        PeriodicTask {
+            reservation,
+            execution,
+        }
+    }
+}
+
+/// The periodic tasks that are simulated together.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TaskSet {
+    /// The tasks, forked in order.
+    pub tasks: Vec<PeriodicTask>,
+
+    /// The tasks release jobs until this time.
+    pub horizon: usize,
+}
+
+/// A job of a periodic task.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Job {
+    /// The index of the task in the [`TaskSet`].
+    pub task: usize,
+
+    /// The PID of the task's process.
+    pub pid: Pid,
+
+    /// The time when the job was released.
+    pub release: usize,
+
+    /// The time when the job has completed, after its last time unit.
+    pub completion: usize,
+
+    /// The time by which the job should have completed.
+    pub deadline: usize,
+}
+
+impl Job {
+    /// The time from the release of the job until it has completed.
+    pub fn response(&self) -> usize {
+        self.completion - self.release
+    }
+
+    /// Whether the job has completed after its deadline.
+    pub fn missed(&self) -> bool {
+        self.completion > self.deadline
+    }
+}
+
+/// Statistics about the jobs of a task.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskMetrics {
+    /// The index of the task in the [`TaskSet`].
+    pub task: usize,
+
+    /// The PID of the task's process, `None` if the scheduler has denied its
+    /// reservation.
+    pub pid: Option<Pid>,
+
+    /// The number of jobs completed.
+    pub jobs: usize,
+
+    /// The number of jobs completed after their deadline.
+    pub missed: usize,
+
+    /// The longest response time of a job.
+    pub worst_response: Option<usize>,
+}
+
+/// The result of simulating a [`TaskSet`].
+#[derive(Debug)]
+pub struct PeriodicRun {
+    /// The logs of the simulation.
+    pub logs: Vec<Log>,
+
+    /// The completed jobs, in the order they have completed.
+    pub jobs: Vec<Job>,
+
+    /// The tasks whose reservation was denied, they were not forked.
+    pub denied: Vec<usize>,
+}
+
+impl PeriodicRun {
+    /// The number of jobs completed after their deadline.
+    pub fn missed(&self) -> usize {
+        self.jobs.iter().filter(|job| job.missed()).count()
+    }
+
+    /// The statistics of every task of the task set.
+    pub fn tasks(&self, task_set: &TaskSet) -> Vec<TaskMetrics> {
+        (0..task_set.tasks.len())
+            .map(|task| {
+                let jobs = self.jobs.iter().filter(|job| job.task == task);
+                TaskMetrics {
+                    task,
+                    pid: jobs.clone().next().map(|job| job.pid),
+                    jobs: jobs.clone().count(),
+                    missed: jobs.clone().filter(|job| job.missed()).count(),
+                    worst_response: jobs.map(Job::response).max(),
+                }
+            })
+            .collect()
+    }
+}
+
+impl TaskSet {
+    /// Simulate the task set with the scheduler.
+    ///
+    /// Every job executes, then waits for the release of the next one with
+    /// a [`Syscall::Sleep`](scheduler::Syscall::Sleep), or gives up the
+    /// processor if it is already late. This system call is the last time
+    /// unit of the job.
+    pub fn run<S: Scheduler + 'static>(&self, scheduler: S, options: Options) -> PeriodicRun {
+        let jobs = Arc::new(Mutex::new(vec![]));
+        let denied = Arc::new(Mutex::new(vec![]));
+        let shared = (jobs.clone(), denied.clone());
+        let task_set = self.clone();
+        let logs = Processor::run_with_options(scheduler, options, move |process| {
+            let (jobs, denied) = shared;
+            let running = Arc::new(AtomicUsize::new(task_set.tasks.len()));
+            for (index, task) in task_set.tasks.iter().copied().enumerate() {
+                let jobs = jobs.clone();
+                let tasks_running = running.clone();
+                let horizon = task_set.horizon;
+                // the task is created when the fork ends
+                let mut release = process.now() + 1;
+                let child = process.fork_reserved(
+                    move |process| {
+                        loop {
+                            for _ in 1..task.execution {
+                                process.exec();
+                            }
+                            let completion = process.now() + 1;
+                            jobs.lock().unwrap().push(Job {
+                                task: index,
+                                pid: process.pid,
+                                release,
+                                completion,
+                                deadline: release + task.reservation.deadline.get(),
+                            });
+                            release += task.reservation.period.get();
+                            if release >= horizon {
+                                break;
+                            }
+                            if completion < release {
+                                process.sleep(release - completion);
+                            } else {
+                                process.yield_now();
+                            }
+                        }
+                        if tasks_running.fetch_sub(1, Ordering::Relaxed) == 1 {
+                            process.signal_latched(DONE);
+                        }
+                    },
+                    0,
+                    task.reservation,
+                );
+                if child.is_none() {
+                    denied.lock().unwrap().push(index);
+                    running.fetch_sub(1, Ordering::Relaxed);
+                }
+            }
+            if running.load(Ordering::Relaxed) > 0 {
+                process.wait(DONE);
+            }
+        });
+        let jobs = jobs.lock().unwrap().clone();
+        let denied = denied.lock().unwrap().clone();
+        PeriodicRun { logs, jobs, denied }
+    }
+}
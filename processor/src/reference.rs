@@ -0,0 +1,509 @@
+//! A deterministic, single threaded simulation of a [`Workload`].
+//!
+//! It takes the same steps as the threaded [`Processor`](crate::Processor),
+//! one process at a time, so both have to produce the same logs. A divergence
+//! points to a synchronization problem in the processor or to a scheduler
+//! that depends on something else than the system calls it receives.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Display};
+use std::num::NonZeroUsize;
+
+use scheduler::{
+    edf, fair_share, priority_queue, rate_monotonic, reservations, round_robin, rt_classes, Aging,
+    GroupConfig, PanicReason, Pid, Scheduler, SchedulerError, SchedulingDecision, StopReason,
+    Syscall, SyscallResult,
+};
+
+use crate::device::DeviceInfo;
+use crate::generator::Rng;
+use crate::workload::signals;
+use crate::{DeadlockReport, GeneratorConfig, Log, Op, Options, ProcessInfo, Workload};
+
+/// The instructions left to a process.
+struct Task<'a> {
+    ops: &'a [Op],
+    next: usize,
+    units: usize,
+}
+
+/// A device request, served from `start` until `completion`.
+struct Request {
+    pid: Pid,
+    start: usize,
+    completion: usize,
+}
+
+/// The reference keeps its own bookkeeping instead of sharing the
+/// processor's: the devices keep every request they received, the
+/// signalers of an event are found in the instructions left to the
+/// processes and the results of the scheduler are checked on their own.
+struct Reference<'a, S: Scheduler> {
+    scheduler: S,
+    options: Options,
+    tasks: BTreeMap<Pid, Task<'a>>,
+    current: Option<Pid>,
+    remaining: usize,
+    timeslice: usize,
+    clock: usize,
+    devices: BTreeMap<usize, Vec<Request>>,
+    last_run: Option<Pid>,
+    logs: Vec<Log>,
+    running: bool,
+}
+
+impl<'a, S: Scheduler> Reference<'a, S> {
+    fn exec(&mut self) -> bool {
+        if self.running {
+            self.remaining = self.remaining.wrapping_sub(1);
+            self.remaining != 0
+        } else {
+            true
+        }
+    }
+
+    fn scheduler(&mut self, mut reason: StopReason) -> SyscallResult {
+        if !self.running {
+            return SyscallResult::NoRunningProcess;
+        }
+
+        self.remaining = self.remaining.wrapping_sub(1);
+        reason.set_remaining(self.remaining);
+        self.clock += match reason {
+            StopReason::Syscall { remaining, .. } => self.timeslice.saturating_sub(remaining),
+            StopReason::Expired => self.timeslice,
+        };
+
+        let completion = match reason {
+            StopReason::Syscall {
+                syscall: Syscall::Io { device, duration },
+                ..
+            } => Some((self.current.unwrap(), self.request(device, duration))),
+            _ => None,
+        };
+
+        let result = self.scheduler.stop(reason);
+        if let Some(log) = self.logs.last_mut() {
+            log.stop_reason = Some((reason, result));
+        }
+
+        self.current = None;
+        if !allowed(reason, result) {
+            self.fail(SchedulerError::UnexpectedResult { reason, result });
+            return result;
+        }
+        if let Some((pid, wakeup)) = completion {
+            self.scheduler.io_completion(pid, wakeup);
+        }
+        while self.running && self.current.is_none() {
+            let next = self.scheduler.next();
+            let processes = self.processes();
+            let context_switch = self.context_switch(next);
+            if let Some(time) = context_switch {
+                self.scheduler.context_switch(time);
+            }
+            let mut log = Log::new(next, None, processes, self.device_list(), context_switch);
+            log.throttle_events = self.scheduler.throttle_events();
+            if next == SchedulingDecision::Deadlock {
+                log.deadlock = Some(DeadlockReport::new(&log.processes, |event| {
+                    self.signalers(event)
+                }));
+            }
+            self.push(log);
+            match next {
+                SchedulingDecision::Run { pid, timeslice } => {
+                    self.remaining = timeslice.get();
+                    self.timeslice = timeslice.get();
+                    self.current = Some(pid);
+                }
+                SchedulingDecision::Sleep(time) => self.clock += time.get(),
+                SchedulingDecision::Deadlock
+                | SchedulingDecision::Panic(_)
+                | SchedulingDecision::Done => self.running = false,
+            }
+        }
+        result
+    }
+
+    fn fail(&mut self, error: SchedulerError) {
+        let mut log = Log::new(
+            SchedulingDecision::Panic(PanicReason::ContractViolation),
+            None,
+            self.processes(),
+            self.device_list(),
+            None,
+        );
+        log.error = Some(error);
+        self.push(log);
+        self.running = false;
+    }
+
+    /// Log a decision taken at the current time, after its context switch.
+    fn push(&mut self, mut log: Log) {
+        log.end_time = self.clock;
+        log.start_time = self.clock - log.context_switch.unwrap_or(0);
+        if let Some(last) = self.logs.last_mut() {
+            last.end_time = log.start_time;
+        }
+        self.logs.push(log);
+    }
+
+    fn processes(&mut self) -> HashMap<Pid, ProcessInfo> {
+        self.scheduler
+            .list()
+            .into_iter()
+            .map(|process| (process.pid(), ProcessInfo::snapshot(process)))
+            .collect()
+    }
+
+    /// The processes that have a signal of `event` among the instructions
+    /// they have not run yet.
+    fn signalers(&self, event: usize) -> Vec<Pid> {
+        self.tasks
+            .iter()
+            .filter(|(_, task)| signals(&task.ops[task.next..]).any(|signal| signal == event))
+            .map(|(pid, _)| *pid)
+            .collect()
+    }
+
+    /// Queue a request of the running process on `device` after the ones
+    /// it has already received and return the time it completes.
+    fn request(&mut self, device: usize, duration: usize) -> usize {
+        let requests = self.devices.entry(device).or_default();
+        let start = requests
+            .last()
+            .map_or(self.clock, |last| last.completion.max(self.clock));
+        requests.push(Request {
+            pid: self.current.unwrap(),
+            start,
+            completion: start + duration,
+        });
+        start + duration
+    }
+
+    fn device_list(&self) -> Vec<DeviceInfo> {
+        let now = self.clock;
+        self.devices
+            .iter()
+            .map(|(&device, requests)| DeviceInfo {
+                device,
+                queue: requests
+                    .iter()
+                    .filter(|request| request.completion > now)
+                    .map(|request| request.pid)
+                    .collect(),
+                requests: requests.len(),
+                busy_time: requests
+                    .iter()
+                    .map(|request| request.completion.min(now) - request.start.min(now))
+                    .sum(),
+            })
+            .collect()
+    }
+
+    fn context_switch(&mut self, decision: SchedulingDecision) -> Option<usize> {
+        let SchedulingDecision::Run { pid, .. } = decision else {
+            return None;
+        };
+
+        let previous = self.last_run.replace(pid);
+        if previous.is_some_and(|previous| previous != pid) {
+            self.clock += self.options.context_switch;
+            Some(self.options.context_switch)
+        } else {
+            None
+        }
+    }
+}
+
+/// Whether the scheduler may answer the system call with `result`: a fork
+/// returns the new PID, a reservation may also be denied, and no other
+/// system call returns a PID. The stopped process was always running.
+fn allowed(reason: StopReason, result: SyscallResult) -> bool {
+    let StopReason::Syscall { syscall, .. } = reason else {
+        return !matches!(
+            result,
+            SyscallResult::Pid(_) | SyscallResult::NoRunningProcess
+        );
+    };
+    match (syscall, result) {
+        (_, SyscallResult::NoRunningProcess) => false,
+        (Syscall::Fork(_) | Syscall::ForkClass { .. }, result) => {
+            matches!(result, SyscallResult::Pid(_))
+        }
+        (Syscall::ForkReserved { .. }, result) => matches!(
+            result,
+            SyscallResult::Pid(_) | SyscallResult::AdmissionDenied
+        ),
+        (_, result) => !matches!(result, SyscallResult::Pid(_)),
+    }
+}
+
+impl Workload {
+    /// Simulate the workload as the process with PID 1, without threads.
+    ///
+    /// The logs are the same as the ones of [`Workload::run`].
+    ///
+    /// * `scheduler` - the scheduler to use for the simulation.
+    /// * `options` - the simulation options.
+    pub fn simulate<S: Scheduler>(&self, scheduler: S, options: Options) -> Vec<Log> {
+        let mut reference = Reference {
+            scheduler,
+            options,
+            tasks: BTreeMap::new(),
+            current: None,
+            remaining: 1,
+            timeslice: 0,
+            clock: 0,
+            devices: BTreeMap::new(),
+            last_run: None,
+            logs: vec![],
+            running: true,
+        };
+
+        let reason = StopReason::syscall(Syscall::Fork(0));
+        let result = reference.scheduler(reason);
+        let pid = Pid::new(1);
+        if result != SyscallResult::Pid(pid) {
+            if reference.running {
+                reference.fail(SchedulerError::UnexpectedResult { reason, result });
+            }
+            return reference.logs;
+        }
+
+        reference.tasks.insert(pid, Task::new(self));
+        while let Some(pid) = reference.current {
+            let Some(task) = reference.tasks.get_mut(&pid) else {
+                panic!("process {pid} is scheduled, but it does not exist");
+            };
+
+            if task.units > 0 {
+                task.units -= 1;
+                if !reference.exec() {
+                    reference.scheduler(StopReason::expired());
+                }
+                continue;
+            }
+
+            let Some(op) = task.ops.get(task.next) else {
+                reference.tasks.remove(&pid);
+                reference.scheduler(StopReason::syscall(Syscall::Exit));
+                continue;
+            };
+            task.next += 1;
+
+            let syscall = match op {
+                Op::Exec(units) => {
+                    task.units = *units;
+                    continue;
+                }
+                Op::Fork(workload) => {
+                    let result =
+                        reference.scheduler(StopReason::syscall(Syscall::Fork(workload.priority)));
+                    if let SyscallResult::Pid(child) = result {
+                        reference.tasks.insert(child, Task::new(workload));
+                    }
+                    continue;
+                }
+                Op::ForkClass { class, workload } => {
+                    let result = reference.scheduler(StopReason::syscall(Syscall::ForkClass {
+                        priority: workload.priority,
+                        class: *class,
+                    }));
+                    if let SyscallResult::Pid(child) = result {
+                        reference.tasks.insert(child, Task::new(workload));
+                    }
+                    continue;
+                }
+                Op::ForkReserved {
+                    reservation,
+                    workload,
+                } => {
+                    let result = reference.scheduler(StopReason::syscall(Syscall::ForkReserved {
+                        priority: workload.priority,
+                        reservation: *reservation,
+                    }));
+                    if let SyscallResult::Pid(child) = result {
+                        reference.tasks.insert(child, Task::new(workload));
+                    }
+                    continue;
+                }
+                Op::Sleep(time) => Syscall::Sleep(*time),
+                Op::Wait(event) => Syscall::Wait(*event),
+                Op::Signal(event) => Syscall::Signal(*event),
+                Op::SignalOne(event) => Syscall::SignalOne(*event),
+                Op::SignalLatched(event) => Syscall::SignalLatched(*event),
+                Op::Io { device, duration } => Syscall::Io {
+                    device: *device,
+                    duration: *duration,
+                },
+                Op::Nice(priority) => Syscall::SetPriority(pid, *priority),
+                Op::Group(group) => Syscall::SetGroup(pid, *group),
+                Op::SetClass(class) => Syscall::SetClass(pid, *class),
+                Op::Yield => Syscall::Yield,
+            };
+            reference.scheduler(StopReason::syscall(syscall));
+        }
+
+        reference.logs
+    }
+}
+
+impl<'a> Task<'a> {
+    fn new(workload: &'a Workload) -> Task<'a> {
+        Task {
+            ops: &workload.ops,
+            next: 0,
+            units: 0,
+        }
+    }
+}
+
+/// The first iteration where the threaded and the reference simulations differ.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergence {
+    /// The iteration number, starting from 1.
+    pub iteration: usize,
+
+    /// The iteration of the threaded [`Processor`](crate::Processor), if it has one.
+    pub threaded: Option<String>,
+
+    /// The iteration of the reference simulation, if it has one.
+    pub reference: Option<String>,
+}
+
+impl Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let none = "no iteration\n".to_string();
+        writeln!(f, "the simulations diverge at iteration {}", self.iteration)?;
+        write!(
+            f,
+            "===== Threaded =====\n{}===== Reference =====\n{}",
+            self.threaded.as_ref().unwrap_or(&none),
+            self.reference.as_ref().unwrap_or(&none)
+        )
+    }
+}
+
+/// Simulate the workload with the threaded [`Processor`](crate::Processor) and with
+/// [`Workload::simulate`] and compare their logs.
+///
+/// * `workload` - the workload to simulate.
+/// * `options` - the simulation options.
+/// * `scheduler` - builds a new scheduler for every simulation.
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::{Op, Options, Workload};
+/// use std::num::NonZeroUsize;
+///
+/// let workload = Workload::new(0, vec![Op::Exec(5), Op::Sleep(2)]);
+/// let scheduler = || scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1);
+/// assert!(processor::compare_engines(&workload, Options::default(), scheduler).is_ok());
+/// ```
+pub fn compare_engines<S, F>(
+    workload: &Workload,
+    options: Options,
+    scheduler: F,
+) -> Result<Vec<Log>, Divergence>
+where
+    S: Scheduler + 'static,
+    F: Fn() -> S,
+{
+    let threaded = workload.run(scheduler(), options);
+    let reference = workload.simulate(scheduler(), options);
+
+    for iteration in 0..threaded.len().max(reference.len()) {
+        let (a, b) = (threaded.get(iteration), reference.get(iteration));
+        if a != b {
+            return Err(Divergence {
+                iteration: iteration + 1,
+                threaded: a.map(|log| log.to_string()),
+                reference: b.map(|log| log.to_string()),
+            });
+        }
+    }
+    Ok(threaded)
+}
+
+/// A simulation built from the bytes given by a fuzzer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzCase {
+    /// The generated workload.
+    pub workload: Workload,
+
+    /// The simulation options.
+    pub options: Options,
+
+    /// The scheduler timeslice.
+    pub timeslice: NonZeroUsize,
+
+    /// The minimum remaining timeslice of the scheduler.
+    pub remaining: usize,
+
+    /// The scheduler, one of [`FuzzCase::SCHEDULERS`].
+    pub scheduler: &'static str,
+}
+
+impl FuzzCase {
+    /// The schedulers that a case can use.
+    pub const SCHEDULERS: [&'static str; 7] = [
+        "round-robin",
+        "priority-queue",
+        "rt-classes",
+        "fair-share",
+        "earliest-deadline",
+        "reservation",
+        "rate-monotonic",
+    ];
+
+    /// Decode the bytes, any input is valid. The first bytes choose the
+    /// parameters, the next eight the seed of the [generator](Workload::generate)
+    /// and the last one the scheduler, the round robin one if it is missing.
+    pub fn from_bytes(data: &[u8]) -> FuzzCase {
+        let byte = |index: usize| data.get(index).copied().unwrap_or(0) as usize;
+        let mut seed = [0; 8];
+        for (index, value) in seed.iter_mut().enumerate() {
+            *value = byte(8 + index) as u8;
+        }
+        let mut rng = Rng::new(u64::from_le_bytes(seed));
+
+        let timeslice = NonZeroUsize::new(1 + byte(0) % 8).unwrap();
+        let config = GeneratorConfig {
+            processes: 1 + byte(2) % 10,
+            max_depth: 1 + byte(3) % 4,
+            steps: 0..=byte(4) % 6,
+            devices: byte(5) % 3,
+            io_chance: 20,
+            events: byte(6) % 8,
+            priority_spread: (byte(7) % 20) as i8,
+            deadlock: rng.chance(20),
+            ..GeneratorConfig::default()
+        };
+
+        FuzzCase {
+            workload: Workload::generate(&config, rng.next_u64()),
+            options: Options {
+                context_switch: byte(1) % 3,
+            },
+            timeslice,
+            remaining: 1 + byte(1) / 3 % timeslice.get(),
+            scheduler: FuzzCase::SCHEDULERS[byte(16) % FuzzCase::SCHEDULERS.len()],
+        }
+    }
+
+    /// Build a new scheduler for the case.
+    pub fn new_scheduler(&self) -> Box<dyn Scheduler> {
+        let (timeslice, remaining) = (self.timeslice, self.remaining);
+        match self.scheduler {
+            "priority-queue" => Box::new(priority_queue(timeslice, remaining, Aging::default())),
+            "rt-classes" => Box::new(rt_classes(timeslice, remaining)),
+            "fair-share" => Box::new(fair_share(timeslice, remaining, GroupConfig::default())),
+            "earliest-deadline" => Box::new(edf(timeslice, remaining)),
+            "reservation" => Box::new(reservations(timeslice, remaining)),
+            "rate-monotonic" => Box::new(rate_monotonic(timeslice, remaining)),
+            _ => Box::new(round_robin(timeslice, remaining)),
+        }
+    }
+}
@@ -0,0 +1,191 @@
+//! Workloads described as data instead of closures.
+//!
+//! A [`Workload`] holds the instructions of a process, including the
+//! workloads of the processes it forks, so it can be loaded from a file,
+//! generated or replayed.
+
+use scheduler::{Reservation, SchedClass, Scheduler};
+
+use crate::{Log, Options, Process, Processor};
+
+/// An instruction of a process.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Op {
+    /// Execute a number of time units, see [`Process::exec`].
+    Exec(usize),
+
+    /// Fork a process that runs the workload, see [`Process::fork`].
+    Fork(Workload),
+
+    /// Fork a process that runs the workload in a scheduling class,
+    /// see [`Process::fork_class`].
+    ForkClass {
+        /// The scheduling class of the new process.
+        class: SchedClass,
+
+        /// The instructions of the new process.
+        workload: Workload,
+    },
+
+    /// Fork a process that runs the workload with a processor reservation,
+    /// see [`Process::fork_reserved`].
+    ForkReserved {
+        /// The reservation of the new process.
+        reservation: Reservation,
+
+        /// The instructions of the new process.
+        workload: Workload,
+    },
+
+    /// Sleep, see [`Process::sleep`].
+    Sleep(usize),
+
+    /// Wait for an event, see [`Process::wait`].
+    Wait(usize),
+
+    /// Signal an event, see [`Process::signal`].
+    Signal(usize),
+
+    /// Signal an event to one process, see [`Process::signal_one`].
+    SignalOne(usize),
+
+    /// Signal an event and latch it, see [`Process::signal_latched`].
+    SignalLatched(usize),
+
+    /// Issue a device request, see [`Process::io`].
+    Io {
+        /// The device number.
+        device: usize,
+
+        /// The amount of time the device needs to serve the request.
+        duration: usize,
+    },
+
+    /// Change the priority of the process, see [`Process::nice`].
+    Nice(i8),
+
+    /// Move the process to a process group, see [`Process::set_group`].
+    Group(usize),
+
+    /// Change the scheduling class of the process, see [`Process::set_class`].
+    SetClass(SchedClass),
+
+    /// Give up the rest of the timeslice, see [`Process::yield_now`].
+    Yield,
+}
+
+/// The instructions of a process.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Workload {
+    /// The priority the process is forked with. It is ignored for
+    /// the process with PID 1, which is always forked with priority 0.
+    pub priority: i8,
+
+    /// The instructions, in the order they are executed.
+    pub ops: Vec<Op>,
+}
+
+impl Workload {
+    /// Create a workload.
+    ///
+    /// * `priority` - the priority the process is forked with.
+    /// * `ops` - the instructions of the process.
+    pub fn new(priority: i8, ops: Vec<Op>) -> Workload {
+        Workload { priority, ops }
+    }
+
+    /// Simulate the workload as the process with PID 1.
+    ///
+    /// * `scheduler` - the scheduler to use for the simulation.
+    /// * `options` - the simulation options.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use processor::{Op, Options, Workload};
+    /// use std::num::NonZeroUsize;
+    ///
+    /// let workload = Workload::new(0, vec![
+    ///     Op::Fork(Workload::new(0, vec![Op::Exec(2), Op::Wait(1)])),
+    ///     Op::Sleep(10),
+    ///     Op::Signal(1),
+    /// ]);
+    /// let scheduler = scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1);
+    /// let logs = workload.run(scheduler, Options::default());
+    /// ```
+    pub fn run<S: Scheduler + 'static>(&self, scheduler: S, options: Options) -> Vec<Log> {
+        Processor::run_with_options(scheduler, options, |process| self.execute(process))
+    }
+
+    /// Execute the instructions as the `process`.
+    ///
+    /// The process declares every event it signals, so a deadlock report
+    /// knows which process the waiting ones wait for.
+    pub fn execute<S: Scheduler + 'static>(&self, process: &Process<S>) {
+        for event in signals(&self.ops) {
+            process.will_signal(event);
+        }
+        for op in self.ops.iter() {
+            match op {
+                Op::Exec(units) => {
+                    for _ in 0..*units {
+                        process.exec();
+                    }
+                }
+                Op::Fork(workload) => {
+                    let child = workload.clone();
+                    process.try_fork(move |process| child.execute(process), workload.priority);
+                }
+                Op::ForkClass { class, workload } => {
+                    let child = workload.clone();
+                    process.fork_class(
+                        move |process| child.execute(process),
+                        workload.priority,
+                        *class,
+                    );
+                }
+                Op::ForkReserved {
+                    reservation,
+                    workload,
+                } => {
+                    let child = workload.clone();
+                    process.fork_reserved(
+                        move |process| child.execute(process),
+                        workload.priority,
+                        *reservation,
+                    );
+                }
+                Op::Sleep(time) => process.sleep(*time),
+                Op::Wait(event) => process.wait(*event),
+                Op::Signal(event) => {
+                    process.signal(*event);
+                }
+                Op::SignalOne(event) => {
+                    process.signal_one(*event);
+                }
+                Op::SignalLatched(event) => {
+                    process.signal_latched(*event);
+                }
+                Op::Io { device, duration } => process.io(*device, *duration),
+                Op::Nice(priority) => {
+                    process.nice(*priority);
+                }
+                Op::Group(group) => {
+                    process.set_group(process.pid, *group);
+                }
+                Op::SetClass(class) => {
+                    process.set_class(process.pid, *class);
+                }
+                Op::Yield => process.yield_now(),
+            }
+        }
+    }
+}
+
+/// The events signaled by the instructions, once for every signal.
+pub(crate) fn signals(ops: &[Op]) -> impl Iterator<Item = usize> + '_ {
+    ops.iter().filter_map(|op| match op {
+        Op::Signal(event) | Op::SignalOne(event) | Op::SignalLatched(event) => Some(*event),
+        _ => None,
+    })
+}
@@ -0,0 +1,315 @@
+//! Command line parsing.
+
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use processor::{GeneratorConfig, Options};
+
+use crate::compare::{DEFAULT_REMAININGS, DEFAULT_TIMESLICES};
+use crate::config::{canonical, parse, SchedulerConfig, SCHEDULERS};
+use crate::export::ExportFormat;
+
+pub const USAGE: &str = "\
+Usage:
+    runner run [OPTIONS] <WORKLOAD>       simulate the workload
+    runner compare [OPTIONS] <WORKLOAD>   compare the schedulers and their parameters
+    runner periodic [OPTIONS] <TASKS>     compare the deadlines the schedulers miss
+    runner export [OPTIONS] <WORKLOAD>    export the simulation to another tool
+    runner generate [GENERATE OPTIONS]    write a random workload
+    runner help                           print this message
+
+Options:
+    -s, --scheduler <NAME>        the scheduler: round-robin (rr), priority-queue (pq),
+                                  rt-classes (rt), fair-share (fs),
+                                  earliest-deadline (edf), reservation (cbs),
+                                  rate-monotonic (rm)
+                                  [default: round-robin]
+    -t, --timeslice <UNITS>       the scheduler timeslice [default: 3]
+    -r, --remaining <UNITS>       the minimum remaining timeslice [default: 1]
+        --cpu-slices <UNITS>      the slices of fair schedulers [default: 10]
+        --adopt-orphans           PID 1 adopts orphans and may exit before them
+                                  (round-robin only)
+        --aging <RATE>            the priority gained per time unit waited [default: 0]
+        --starvation <UNITS>      report the processes ready for longer without running
+        --weight <GROUP>=<WEIGHT> the CPU weight of a process group [default: 100]
+        --quota <GROUP>=<QUOTA>/<PERIOD>
+                                  the processor time a group may use in every period
+    -c, --context-switch <UNITS>  the time consumed by a context switch [default: 0]
+    -f, --format <FORMAT>         run: logs, metrics, decision [default: logs]
+                                  compare: table, csv [default: table]
+                                  export: chrome, csv [default: chrome]
+    -o, --output <FILE>           export, generate: the file to write [default: stdout]
+
+Generate options:
+        --seed <NUMBER>           the same seed generates the same workload [default: 0]
+    -p, --processes <NUMBER>      the number of processes [default: 5]
+        --depth <NUMBER>          the maximum depth of the fork tree [default: 3]
+        --events <NUMBER>         the number of wait/signal pairs [default: 3]
+        --devices <NUMBER>        the number of devices used [default: 0]
+        --priority-spread <N>     the priorities are between -N and N [default: 0]
+        --class-chance <PERCENT>  the chance that a process gets a scheduling class [default: 0]
+        --reserved-chance <PERCENT>
+                                  the chance that a process gets a reservation [default: 0]
+        --deadlock                make the workload deadlock
+
+compare simulates every scheduler, or only --scheduler if given, with every
+combination of --timeslice and --remaining, which accept comma separated lists
+[default: 1,2,3,5,8 and 1,2].
+periodic simulates the periodic TASKS the same way, every task is forked with
+its reservation [default: --timeslice and --remaining], and compares the
+rate-monotonic response times with the predicted worst case.
+The WORKLOAD is a TOML file. The exit code is 2 if the simulation ends in
+a deadlock and 3 if it ends in a panic.";
+
+/// The output of the `run` command.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RunFormat {
+    /// The logs of every iteration.
+    Logs,
+
+    /// The statistics of the simulation.
+    Metrics,
+
+    /// The last scheduling decision.
+    Decision,
+}
+
+/// The output of the `compare` command.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CompareFormat {
+    /// A table with aligned columns.
+    Table,
+
+    /// Comma separated values.
+    Csv,
+}
+
+/// A command given to the runner.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Run {
+        config: SchedulerConfig,
+        options: Options,
+        format: RunFormat,
+        workload: PathBuf,
+    },
+    Compare {
+        configs: Vec<SchedulerConfig>,
+        options: Options,
+        format: CompareFormat,
+        workload: PathBuf,
+    },
+    Periodic {
+        configs: Vec<SchedulerConfig>,
+        options: Options,
+        tasks: PathBuf,
+    },
+    Export {
+        config: SchedulerConfig,
+        options: Options,
+        format: ExportFormat,
+        output: Option<PathBuf>,
+        workload: PathBuf,
+    },
+    Generate {
+        config: GeneratorConfig,
+        seed: u64,
+        output: Option<PathBuf>,
+    },
+    Help,
+}
+
+/// Parse the command line arguments, without the program name.
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Command, String> {
+    let mut args = args.into_iter();
+    let command = args.next().ok_or("no command given")?;
+    if matches!(command.as_str(), "help" | "-h" | "--help") {
+        return Ok(Command::Help);
+    }
+    if command == "generate" {
+        return parse_generate(args);
+    }
+
+    let mut config = SchedulerConfig::default();
+    let mut options = Options::default();
+    let mut scheduler = None;
+    let mut timeslices = None;
+    let mut remainings = None;
+    let mut format = None;
+    let mut output = None;
+    let mut workload = None;
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("{arg} needs a value"));
+        match arg.as_str() {
+            "-s" | "--scheduler" => scheduler = Some(canonical(&value()?)?),
+            "-t" | "--timeslice" => timeslices = Some(value()?),
+            "-r" | "--remaining" => remainings = Some(value()?),
+            "--cpu-slices" => config.cpu_slices = parse(&arg, &value()?)?,
+            "--adopt-orphans" => config.adopt_orphans = true,
+            "--aging" => config.aging.rate = parse(&arg, &value()?)?,
+            "--starvation" => config.aging.starvation = Some(parse(&arg, &value()?)?),
+            "--weight" => {
+                let (group, weight) = pair(&arg, &value()?)?;
+                config.groups.weights.insert(group, weight);
+            }
+            "--quota" => {
+                let (group, bandwidth) = pair(&arg, &value()?)?;
+                config.groups.bandwidth.insert(group, bandwidth);
+            }
+            "-c" | "--context-switch" => options.context_switch = parse(&arg, &value()?)?,
+            "-f" | "--format" => format = Some(value()?),
+            "-o" | "--output" => output = Some(PathBuf::from(value()?)),
+            _ if arg.starts_with('-') => return Err(format!("unknown option {arg}")),
+            _ if workload.is_none() => workload = Some(PathBuf::from(arg)),
+            _ => return Err(format!("unexpected argument {arg}")),
+        }
+    }
+
+    let workload = workload.ok_or("no workload file given")?;
+
+    if command == "compare" || command == "periodic" {
+        let periodic = command == "periodic";
+        let schedulers = scheduler.map_or(SCHEDULERS.to_vec(), |scheduler| vec![scheduler]);
+        let timeslices = match timeslices {
+            Some(timeslices) => list::<NonZeroUsize>("--timeslice", &timeslices)?,
+            None if periodic => vec![config.timeslice],
+            None => DEFAULT_TIMESLICES
+                .iter()
+                .filter_map(|timeslice| NonZeroUsize::new(*timeslice))
+                .collect(),
+        };
+        let remainings = match remainings {
+            Some(remainings) => list("--remaining", &remainings)?,
+            None if periodic => vec![config.remaining],
+            None => DEFAULT_REMAININGS.to_vec(),
+        };
+
+        let mut configs = vec![];
+        for name in schedulers.iter() {
+            for timeslice in timeslices.iter() {
+                for remaining in remainings.iter() {
+                    configs.push(SchedulerConfig {
+                        name: name.to_string(),
+                        timeslice: *timeslice,
+                        remaining: *remaining,
+                        ..config.clone()
+                    });
+                }
+            }
+        }
+
+        if periodic {
+            return match format {
+                None => Ok(Command::Periodic {
+                    configs,
+                    options,
+                    tasks: workload,
+                }),
+                Some(_) => Err("periodic has no --format".to_string()),
+            };
+        }
+
+        return Ok(Command::Compare {
+            configs,
+            options,
+            format: match format.as_deref() {
+                None | Some("table") => CompareFormat::Table,
+                Some("csv") => CompareFormat::Csv,
+                Some(format) => {
+                    return Err(format!(
+                        "unknown compare format `{format}`, available: table, csv"
+                    ))
+                }
+            },
+            workload,
+        });
+    }
+
+    if let Some(scheduler) = scheduler {
+        config.name = scheduler.to_string();
+    }
+    if let Some(timeslice) = timeslices {
+        config.timeslice = parse("--timeslice", &timeslice)?;
+    }
+    if let Some(remaining) = remainings {
+        config.remaining = parse("--remaining", &remaining)?;
+    }
+
+    match command.as_str() {
+        "run" => Ok(Command::Run {
+            config,
+            options,
+            format: match format.as_deref() {
+                None | Some("logs") => RunFormat::Logs,
+                Some("metrics") => RunFormat::Metrics,
+                Some("decision") => RunFormat::Decision,
+                Some(format) => {
+                    return Err(format!(
+                        "unknown run format `{format}`, available: logs, metrics, decision"
+                    ))
+                }
+            },
+            workload,
+        }),
+        "export" => Ok(Command::Export {
+            config,
+            options,
+            format: ExportFormat::parse(format.as_deref().unwrap_or("chrome"))?,
+            output,
+            workload,
+        }),
+        _ => Err(format!("unknown command {command}")),
+    }
+}
+
+/// Parse the arguments of the `generate` command.
+fn parse_generate<I: Iterator<Item = String>>(mut args: I) -> Result<Command, String> {
+    let mut config = GeneratorConfig::default();
+    let mut seed = 0;
+    let mut output = None;
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("{arg} needs a value"));
+        match arg.as_str() {
+            "--seed" => seed = parse(&arg, &value()?)?,
+            "-p" | "--processes" => config.processes = parse(&arg, &value()?)?,
+            "--depth" => config.max_depth = parse(&arg, &value()?)?,
+            "--events" => config.events = parse(&arg, &value()?)?,
+            "--devices" => {
+                config.devices = parse(&arg, &value()?)?;
+                config.io_chance = if config.devices > 0 { 20 } else { 0 };
+            }
+            "--priority-spread" => config.priority_spread = parse(&arg, &value()?)?,
+            "--class-chance" => config.class_chance = parse(&arg, &value()?)?,
+            "--reserved-chance" => config.reserved_chance = parse(&arg, &value()?)?,
+            "--deadlock" => config.deadlock = true,
+            "-o" | "--output" => output = Some(PathBuf::from(value()?)),
+            _ if arg.starts_with('-') => return Err(format!("unknown option {arg}")),
+            _ => return Err(format!("unexpected argument {arg}")),
+        }
+    }
+
+    Ok(Command::Generate {
+        config,
+        seed,
+        output,
+    })
+}
+
+/// Parse a `<KEY>=<VALUE>` argument.
+fn pair<K: FromStr, V: FromStr>(name: &str, value: &str) -> Result<(K, V), String> {
+    let (key, rest) = value
+        .split_once('=')
+        .ok_or(format!("invalid value `{value}` for {name}"))?;
+    Ok((parse(name, key.trim())?, parse(name, rest.trim())?))
+}
+
+/// Parse a comma separated list of values.
+fn list<T: FromStr>(name: &str, values: &str) -> Result<Vec<T>, String> {
+    values
+        .split(',')
+        .map(|value| parse(name, value.trim()))
+        .collect()
+}
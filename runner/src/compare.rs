@@ -0,0 +1,181 @@
+//! Simulating a workload with several schedulers and parameters.
+
+use std::fmt::Write;
+
+use processor::{decimal, Analysis, Metrics, Options, TaskMetrics, TaskSet, Workload};
+use scheduler::SchedulingDecision;
+
+use crate::config::{canonical, SchedulerConfig};
+
+/// The timeslices compared when none are given.
+pub const DEFAULT_TIMESLICES: &[usize] = &[1, 2, 3, 5, 8];
+
+/// The minimum remaining timeslices compared when none are given.
+pub const DEFAULT_REMAININGS: &[usize] = &[1, 2];
+
+/// The result of simulating the workload with a scheduler.
+pub struct Row {
+    /// The scheduler and its parameters.
+    pub config: SchedulerConfig,
+
+    /// The statistics of the simulation.
+    pub metrics: Metrics,
+
+    /// The last scheduling decision.
+    pub decision: Option<SchedulingDecision>,
+}
+
+/// Simulate the workload with every scheduler configuration.
+pub fn compare(
+    workload: &Workload,
+    configs: &[SchedulerConfig],
+    options: Options,
+) -> Result<Vec<Row>, String> {
+    configs
+        .iter()
+        .map(|config| {
+            let logs = workload.run(config.build()?, options);
+            Ok(Row {
+                config: config.clone(),
+                metrics: Metrics::from_logs(&logs),
+                decision: logs.last().map(|log| log.decision),
+            })
+        })
+        .collect()
+}
+
+/// The result of simulating a periodic task set with a scheduler.
+pub struct PeriodicRow {
+    /// The scheduler and its parameters.
+    pub config: SchedulerConfig,
+
+    /// The statistics of every task.
+    pub tasks: Vec<TaskMetrics>,
+
+    /// The response time analysis of the task set, for the rate monotonic
+    /// scheduler.
+    pub analysis: Option<Analysis>,
+}
+
+/// Simulate the task set with every scheduler configuration.
+pub fn compare_periodic(
+    task_set: &TaskSet,
+    configs: &[SchedulerConfig],
+    options: Options,
+) -> Result<Vec<PeriodicRow>, String> {
+    configs
+        .iter()
+        .map(|config| {
+            let run = task_set.run(config.build()?, options);
+            // a process with a lower priority keeps the processor until
+            // its timeslice expires
+            let blocking = config.timeslice.get() - 1;
+            Ok(PeriodicRow {
+                config: config.clone(),
+                tasks: run.tasks(task_set),
+                analysis: (canonical(&config.name)? == "rate-monotonic")
+                    .then(|| Analysis::rate_monotonic(task_set, blocking)),
+            })
+        })
+        .collect()
+}
+
+/// Format the results as a table, with a line for every task.
+pub fn format_periodic_table(rows: &[PeriodicRow]) -> String {
+    let mut s = format!(
+        "{:<18}{:>6}{:>7}{:>6}{:>8}{:>6}{:>8}{:>7}{:>11}\n",
+        "SCHEDULER", "SLICE", "REMAIN", "TASK", "PID", "JOBS", "MISSED", "WORST", "PREDICTED"
+    );
+    for row in rows.iter() {
+        for task in row.tasks.iter() {
+            writeln!(
+                s,
+                "{:<18}{:>6}{:>7}{:>6}{:>8}{:>6}{:>8}{:>7}{:>11}",
+                row.config.name,
+                row.config.timeslice,
+                row.config.remaining,
+                task.task,
+                task.pid
+                    .map_or_else(|| "denied".to_string(), |pid| pid.to_string()),
+                task.jobs,
+                task.missed,
+                task.worst_response
+                    .map_or_else(|| "-".to_string(), |worst| worst.to_string()),
+                match &row.analysis {
+                    Some(analysis) => analysis.response_times[task.task]
+                        .map_or_else(|| "miss".to_string(), |predicted| predicted.to_string()),
+                    None => "-".to_string(),
+                },
+            )
+            .unwrap();
+        }
+    }
+    s
+}
+
+/// Format the results as a table.
+pub fn format_table(rows: &[Row]) -> String {
+    let mut s = String::new();
+    let header = [
+        "SCHEDULER",
+        "SLICE",
+        "REMAIN",
+        "TURNAROUND",
+        "WAITING",
+        "RESPONSE",
+        "SWITCHES",
+        "TIME",
+        "DECISION",
+    ]
+    .map(String::from);
+    for values in [header].into_iter().chain(rows.iter().map(values)) {
+        writeln!(
+            s,
+            "{:<18}{:>6}{:>7}{:>11}{:>9}{:>9}{:>9}{:>6}  {}",
+            values[0],
+            values[1],
+            values[2],
+            values[3],
+            values[4],
+            values[5],
+            values[6],
+            values[7],
+            values[8]
+        )
+        .unwrap();
+    }
+    s
+}
+
+/// Format the results as comma separated values.
+pub fn format_csv(rows: &[Row]) -> String {
+    let mut s = String::from(
+        "scheduler,timeslice,remaining,turnaround,waiting,response,context_switches,time,decision\n",
+    );
+    for row in rows.iter() {
+        let values = values(row).map(|value| {
+            if value.contains(',') {
+                format!("\"{value}\"")
+            } else {
+                value
+            }
+        });
+        writeln!(s, "{}", values.join(",")).unwrap();
+    }
+    s
+}
+
+fn values(row: &Row) -> [String; 9] {
+    [
+        row.config.name.clone(),
+        row.config.timeslice.to_string(),
+        row.config.remaining.to_string(),
+        decimal(row.metrics.average_turnaround()),
+        decimal(row.metrics.average_waiting()),
+        decimal(row.metrics.average_response()),
+        row.metrics.context_switches.to_string(),
+        row.metrics.time.to_string(),
+        row.decision
+            .map_or_else(|| "-".to_string(), |decision| decision.to_string()),
+    ]
+}
@@ -0,0 +1,116 @@
+//! The schedulers that the runner can simulate and their parameters.
+
+use std::num::NonZeroUsize;
+
+use scheduler::{
+    edf, fair_share, priority_queue, rate_monotonic, reservations, round_robin,
+    round_robin_adopting_orphans, rt_classes, Aging, GroupConfig, Scheduler,
+};
+
+/// The names of the available schedulers.
+pub const SCHEDULERS: &[&str] = &[
+    "round-robin",
+    "priority-queue",
+    "rt-classes",
+    "fair-share",
+    "earliest-deadline",
+    "reservation",
+    "rate-monotonic",
+];
+
+/// A scheduler and the parameters it is built with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchedulerConfig {
+    /// The scheduler name, one of [`SCHEDULERS`] or an alias.
+    pub name: String,
+
+    /// The time quanta that a process can run before it is preempted.
+    pub timeslice: NonZeroUsize,
+
+    /// The minimum remaining timeslice for a process to be scheduled
+    /// again after a system call.
+    pub remaining: usize,
+
+    /// The number of slices that fair schedulers divide among processes.
+    pub cpu_slices: usize,
+
+    /// Orphans are adopted by the process with PID 1 and the simulation
+    /// goes on after PID 1 exits, instead of a panic. Only the round robin
+    /// scheduler supports it.
+    pub adopt_orphans: bool,
+
+    /// The aging and the starvation threshold of priority schedulers.
+    pub aging: Aging,
+
+    /// The CPU weights of the process groups, for the group schedulers.
+    pub groups: GroupConfig,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> SchedulerConfig {
+        SchedulerConfig {
+            name: SCHEDULERS[0].to_string(),
+            timeslice: NonZeroUsize::new(3).unwrap(),
+            remaining: 1,
+            cpu_slices: 10,
+            adopt_orphans: false,
+            aging: Aging::default(),
+            groups: GroupConfig::default(),
+        }
+    }
+}
+
+impl SchedulerConfig {
+    /// Build the scheduler.
+    pub fn build(&self) -> Result<Box<dyn Scheduler>, String> {
+        match canonical(&self.name)? {
+            "round-robin" if self.adopt_orphans => Ok(Box::new(round_robin_adopting_orphans(
+                self.timeslice,
+                self.remaining,
+            ))),
+            "round-robin" => Ok(Box::new(round_robin(self.timeslice, self.remaining))),
+            name if self.adopt_orphans => Err(format!(
+                "scheduler `{name}` does not support adopting orphans"
+            )),
+            "priority-queue" => Ok(Box::new(priority_queue(
+                self.timeslice,
+                self.remaining,
+                self.aging,
+            ))),
+            "rt-classes" => Ok(Box::new(rt_classes(self.timeslice, self.remaining))),
+            "fair-share" => Ok(Box::new(fair_share(
+                self.timeslice,
+                self.remaining,
+                self.groups.clone(),
+            ))),
+            "earliest-deadline" => Ok(Box::new(edf(self.timeslice, self.remaining))),
+            "reservation" => Ok(Box::new(reservations(self.timeslice, self.remaining))),
+            "rate-monotonic" => Ok(Box::new(rate_monotonic(self.timeslice, self.remaining))),
+            name => Err(format!("scheduler `{name}` cannot be built")),
+        }
+    }
+}
+
+/// Return the name from [`SCHEDULERS`] of a scheduler name or alias.
+pub fn canonical(name: &str) -> Result<&'static str, String> {
+    match name {
+        "rr" | "round-robin" => Ok("round-robin"),
+        "pq" | "priority-queue" => Ok("priority-queue"),
+        "rt" | "rt-classes" => Ok("rt-classes"),
+        "fs" | "fair-share" => Ok("fair-share"),
+        "edf" | "earliest-deadline" => Ok("earliest-deadline"),
+        "cbs" | "reservation" => Ok("reservation"),
+        "rm" | "rate-monotonic" => Ok("rate-monotonic"),
+        _ => Err(format!(
+            "unknown scheduler `{name}`, available: {}",
+            SCHEDULERS.join(", ")
+        )),
+    }
+}
+
+/// Parse a command line value.
+pub fn parse<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value `{value}` for {name}"))
+}
@@ -0,0 +1,109 @@
+//! Exporting the [`Processor`](processor::Processor)'s logs to other tools.
+
+use std::fmt::Write;
+
+use processor::Log;
+use scheduler::SchedulingDecision;
+
+/// The formats the logs can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    /// The Trace Event Format, viewable in `chrome://tracing` or Perfetto.
+    Chrome,
+
+    /// One line of comma separated values per iteration.
+    Csv,
+}
+
+impl ExportFormat {
+    pub fn parse(format: &str) -> Result<ExportFormat, String> {
+        match format {
+            "chrome" => Ok(ExportFormat::Chrome),
+            "csv" => Ok(ExportFormat::Csv),
+            _ => Err(format!(
+                "unknown export format `{format}`, available: chrome, csv"
+            )),
+        }
+    }
+}
+
+/// Export the logs to the format.
+pub fn export(logs: &[Log], format: ExportFormat) -> String {
+    match format {
+        ExportFormat::Chrome => chrome(logs),
+        ExportFormat::Csv => csv(logs),
+    }
+}
+
+fn chrome(logs: &[Log]) -> String {
+    let mut events = vec![];
+    for log in logs.iter() {
+        let start = log.start_time;
+        let switch = log.context_switch.unwrap_or(0);
+        if switch > 0 {
+            events.push(format!(
+                "{{\"name\":\"Context switch\",\"cat\":\"switch\",\"ph\":\"X\",\"ts\":{start},\"dur\":{switch},\"pid\":1,\"tid\":0}}"
+            ));
+        }
+
+        let stop = log
+            .stop_reason
+            .map(|(reason, result)| format!("{reason} -> {result:?}"))
+            .unwrap_or_default();
+        match log.decision {
+            SchedulingDecision::Run { pid, .. } => events.push(format!(
+                "{{\"name\":\"Run {pid}\",\"cat\":\"run\",\"ph\":\"X\",\"ts\":{},\"dur\":{},\"pid\":1,\"tid\":{pid},\"args\":{{\"stop\":\"{}\"}}}}",
+                start + switch,
+                log.run_time(),
+                escape(&stop)
+            )),
+            SchedulingDecision::Sleep(time) => events.push(format!(
+                "{{\"name\":\"Sleep\",\"cat\":\"sleep\",\"ph\":\"X\",\"ts\":{start},\"dur\":{time},\"pid\":1,\"tid\":0}}"
+            )),
+            decision => events.push(format!(
+                "{{\"name\":\"{}\",\"cat\":\"end\",\"ph\":\"i\",\"s\":\"g\",\"ts\":{start},\"pid\":1,\"tid\":0}}",
+                escape(&decision.to_string())
+            )),
+        }
+    }
+
+    format!("{{\"traceEvents\":[\n{}\n]}}\n", events.join(",\n"))
+}
+
+fn csv(logs: &[Log]) -> String {
+    let mut s = String::from("iteration,start,end,decision,context_switch,stop_reason,result\n");
+    for (iteration, log) in logs.iter().enumerate() {
+        let (reason, result) = log
+            .stop_reason
+            .map(|(reason, result)| (reason.to_string(), format!("{result:?}")))
+            .unwrap_or_default();
+        writeln!(
+            s,
+            "{},{},{},\"{}\",{},\"{}\",\"{}\"",
+            iteration + 1,
+            log.start_time,
+            log.end_time,
+            log.decision,
+            log.context_switch.unwrap_or(0),
+            reason.replace('"', "\"\""),
+            result.replace('"', "\"\"")
+        )
+        .unwrap();
+    }
+    s
+}
+
+/// Escape a string for a JSON string literal.
+fn escape(s: &str) -> String {
+    let mut escaped = String::new();
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if c.is_control() => write!(escaped, "\\u{:04x}", c as u32).unwrap(),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
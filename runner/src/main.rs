@@ -1,29 +1,144 @@
-use scheduler::round_robin;
-use std::num::NonZeroUsize;
-
-use processor::format_logs;
-use processor::Processor;
-
-fn main() {
-    let logs = Processor::run(round_robin(NonZeroUsize::new(2).unwrap(), 1), |process| {
-        process.exec();
-        process.exec();
-        process.exec();
-        process.exec();
-        process.fork(
-            |process| {
-                process.exec();
-                process.exec();
-                process.wait(1);
-            },
-            0,
-        );
-        process.sleep(10);
-        process.signal(1);
-        process.exec();
-    });
-
-    println!("{}", format_logs(&logs));
+use std::env;
+use std::fs;
+use std::process::ExitCode;
+
+use processor::{format_logs, Analysis, Log, Metrics, Workload};
+use scheduler::SchedulingDecision;
+
+mod cli;
+mod compare;
+mod config;
+mod export;
+mod task_set;
+mod workload;
+
+use cli::{Command, CompareFormat, RunFormat, USAGE};
+
+fn main() -> ExitCode {
+    let command = match cli::parse_args(env::args().skip(1)) {
+        Ok(command) => command,
+        Err(error) => {
+            eprintln!("error: {error}\n\n{USAGE}");
+            return ExitCode::from(1);
+        }
+    };
+
+    match execute(command) {
+        Ok(decision) => exit_code(decision),
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::from(1)
+        }
+    }
+}
+
+/// Execute the command and return the last scheduling decision.
+fn execute(command: Command) -> Result<Option<SchedulingDecision>, String> {
+    match command {
+        Command::Run {
+            config,
+            options,
+            format,
+            workload,
+        } => {
+            let workload = workload::load(&workload)?;
+            let logs = workload.run(config.build()?, options);
+            match format {
+                RunFormat::Logs => print!("{}", format_logs(&logs)),
+                RunFormat::Metrics => {
+                    let metrics = Metrics::from_logs(&logs);
+                    print!("{metrics}");
+                    if let Some(threshold) = config.aging.starvation {
+                        let starving = metrics.starving(threshold);
+                        let pids = starving.iter().map(|pid| pid.to_string());
+                        println!("Starving: {}", pids.collect::<Vec<String>>().join(", "));
+                    }
+                }
+                RunFormat::Decision => {}
+            }
+            let decision = last_decision(&logs);
+            if let Some(decision) = decision {
+                println!("{decision}");
+            }
+            Ok(decision)
+        }
+        Command::Compare {
+            configs,
+            options,
+            format,
+            workload,
+        } => {
+            let workload = workload::load(&workload)?;
+            let rows = compare::compare(&workload, &configs, options)?;
+            match format {
+                CompareFormat::Table => print!("{}", compare::format_table(&rows)),
+                CompareFormat::Csv => print!("{}", compare::format_csv(&rows)),
+            }
+            Ok(None)
+        }
+        Command::Periodic {
+            configs,
+            options,
+            tasks,
+        } => {
+            let task_set = task_set::load(&tasks)?;
+            let rows = compare::compare_periodic(&task_set, &configs, options)?;
+            print!("{}", compare::format_periodic_table(&rows));
+            let analysis = Analysis::rate_monotonic(&task_set, 0);
+            println!(
+                "Utilisation {:.2}, Liu & Layland bound {:.2}",
+                analysis.utilisation, analysis.bound
+            );
+            Ok(None)
+        }
+        Command::Export {
+            config,
+            options,
+            format,
+            output,
+            workload,
+        } => {
+            let workload = workload::load(&workload)?;
+            let logs = workload.run(config.build()?, options);
+            let exported = export::export(&logs, format);
+            match output {
+                Some(path) => fs::write(&path, exported)
+                    .map_err(|error| format!("cannot write {}: {error}", path.display()))?,
+                None => print!("{exported}"),
+            }
+            Ok(last_decision(&logs))
+        }
+        Command::Generate {
+            config,
+            seed,
+            output,
+        } => {
+            let workload = workload::format(&Workload::generate(&config, seed));
+            match output {
+                Some(path) => fs::write(&path, workload)
+                    .map_err(|error| format!("cannot write {}: {error}", path.display()))?,
+                None => print!("{workload}"),
+            }
+            Ok(None)
+        }
+        Command::Help => {
+            println!("{USAGE}");
+            Ok(None)
+        }
+    }
+}
+
+fn last_decision(logs: &[Log]) -> Option<SchedulingDecision> {
+    logs.last().map(|log| log.decision)
+}
+
+/// A simulation that ends in a deadlock or a panic is a failure.
+fn exit_code(decision: Option<SchedulingDecision>) -> ExitCode {
+    match decision {
+        Some(SchedulingDecision::Deadlock) => ExitCode::from(2),
+        Some(SchedulingDecision::Panic(_)) => ExitCode::from(3),
+        _ => ExitCode::SUCCESS,
+    }
 }
 
 // Do not delete this line
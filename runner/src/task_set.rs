@@ -0,0 +1,82 @@
+//! Loading periodic task sets as TOML files.
+//!
+//! A task set file gives the time until which the tasks release jobs and
+//! the reservation of every task. The deadline of a task is its period if
+//! it is not given, and its jobs use the runtime it declares unless an
+//! `execution` time is given:
+//!
+//! ```toml
+//! horizon = 100
+//! tasks = [
+//!     { runtime = 2, deadline = 5, period = 10 },
+//!     { runtime = 3, period = 15, execution = 5 },
+//! ]
+//! ```
+
+use std::fs;
+use std::path::Path;
+
+use processor::{PeriodicTask, TaskSet};
+use scheduler::Reservation;
+use toml::{Table, Value};
+
+use crate::workload::integer;
+
+/// Read and parse a task set file.
+pub fn load(path: &Path) -> Result<TaskSet, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|error| format!("cannot read {}: {error}", path.display()))?;
+    parse(&text).map_err(|error| format!("{}: {error}", path.display()))
+}
+
+/// Parse the text of a task set file.
+pub fn parse(text: &str) -> Result<TaskSet, String> {
+    let table = text.parse::<Table>().map_err(|error| error.to_string())?;
+    let mut task_set = TaskSet::default();
+    for (key, value) in table.iter() {
+        match key.as_str() {
+            "horizon" => task_set.horizon = integer(value, "horizon")?,
+            "tasks" => {
+                let tasks = value.as_array().ok_or("tasks must be an array")?;
+                for (index, value) in tasks.iter().enumerate() {
+                    task_set
+                        .tasks
+                        .push(task(value, &format!("tasks[{index}]"))?);
+                }
+            }
+            _ => return Err(format!("unknown key {key}")),
+        }
+    }
+    if task_set.horizon == 0 {
+        return Err("horizon is missing".to_string());
+    }
+    Ok(task_set)
+}
+
+fn task(value: &Value, path: &str) -> Result<PeriodicTask, String> {
+    let table = value
+        .as_table()
+        .ok_or_else(|| format!("{path} must be a table"))?;
+    if let Some(key) = table
+        .keys()
+        .find(|key| !["runtime", "deadline", "period", "execution"].contains(&key.as_str()))
+    {
+        return Err(format!("unknown key {path}.{key}"));
+    }
+    let field = |key: &str| {
+        table
+            .get(key)
+            .map(|value| integer::<usize>(value, &format!("{path}.{key}")))
+            .transpose()
+    };
+    let runtime = field("runtime")?.ok_or_else(|| format!("{path}.runtime is missing"))?;
+    let period = field("period")?.ok_or_else(|| format!("{path}.period is missing"))?;
+    let deadline = field("deadline")?.unwrap_or(period);
+    let reservation = Reservation::try_new(runtime, deadline, period)
+        .map_err(|error| format!("{path}: {error}"))?;
+    match field("execution")? {
+        Some(0) => Err(format!("{path}.execution must not be 0")),
+        Some(execution) => Ok(PeriodicTask::overrunning(reservation, execution)),
+        None => Ok(PeriodicTask::new(reservation)),
+    }
+}
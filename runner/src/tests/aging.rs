@@ -0,0 +1,69 @@
+use std::num::NonZeroUsize;
+
+use processor::{Log, Metrics, Processor};
+use scheduler::{priority_queue, Aging, Pid, Scheduler};
+
+use super::check_policy;
+
+fn scheduler(aging: Aging) -> impl Scheduler {
+    priority_queue(NonZeroUsize::new(3).unwrap(), 1, aging)
+}
+
+/// Process 2 keeps the processor with a larger priority than
+/// processes 1 and 3.
+fn hog(aging: Aging) -> Vec<Log> {
+    Processor::run(scheduler(aging), |process| {
+        process.fork(
+            |process| {
+                process.fork(|process| process.exec(), 1);
+                for _ in 0..30 {
+                    process.exec();
+                }
+            },
+            5,
+        );
+        process.sleep(60);
+    })
+}
+
+fn first_run(metrics: &Metrics, pid: usize) -> usize {
+    metrics.processes[pid - 1].first_run.unwrap()
+}
+
+#[test]
+pub fn starvation() {
+    let logs = hog(Aging::new(0, Some(10)));
+    let metrics = Metrics::from_logs(&logs);
+    assert!(first_run(&metrics, 3) > 30);
+    assert_eq!(metrics.starving(10), [Pid::new(1), Pid::new(3)]);
+    let starving = |log: &Log, pid| {
+        log.processes
+            .get(&Pid::new(pid))
+            .is_some_and(|process| process.ext.ends_with("starving"))
+    };
+    assert!(logs.iter().any(|log| starving(log, 3)));
+    assert!(!logs.iter().any(|log| starving(log, 2)));
+}
+
+#[test]
+pub fn aging() {
+    let logs = hog(Aging::new(1, Some(10)));
+    let metrics = Metrics::from_logs(&logs);
+    assert!(first_run(&metrics, 3) < 10);
+    assert!(metrics.starving(10).is_empty());
+    assert!(logs.iter().all(|log| log
+        .processes
+        .values()
+        .all(|process| !process.ext.ends_with("starving"))));
+}
+
+#[test]
+pub fn invariants_and_reference() {
+    for aging in [
+        Aging::default(),
+        Aging::new(1, None),
+        Aging::new(1, Some(5)),
+    ] {
+        check_policy(|| scheduler(aging));
+    }
+}
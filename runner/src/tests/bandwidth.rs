@@ -0,0 +1,159 @@
+use std::collections::BTreeMap;
+use std::num::NonZeroUsize;
+
+use processor::{Log, Op, Processor};
+use scheduler::{
+    fair_share, Bandwidth, GroupConfig, Pid, ProcessState, Scheduler, SchedulingDecision,
+    ThrottleEvent,
+};
+
+use super::{check_policy_with, policy_config};
+use crate::cli::{parse_args, Command};
+
+fn scheduler(groups: GroupConfig) -> impl Scheduler {
+    fair_share(NonZeroUsize::new(3).unwrap(), 1, groups)
+}
+
+fn limited() -> GroupConfig {
+    GroupConfig::default().bandwidth(1, Bandwidth::new(2, 10))
+}
+
+/// Process 2 moves to group 1 and executes 10 time units, process 3
+/// stays in group 0 and executes `execs` time units.
+fn run(execs: usize) -> Vec<Log> {
+    Processor::run(scheduler(limited()), move |process| {
+        process.fork(
+            |process| {
+                process.set_group(process.pid, 1);
+                for _ in 0..10 {
+                    process.exec();
+                }
+            },
+            0,
+        );
+        process.fork(
+            move |process| {
+                for _ in 0..execs {
+                    process.exec();
+                }
+            },
+            0,
+        );
+        process.sleep(100);
+    })
+}
+
+/// The processor time used by the group in every period of 10 time units.
+fn used_by_period(logs: &[Log], group: usize) -> BTreeMap<usize, usize> {
+    let mut used = BTreeMap::new();
+    for log in logs.iter() {
+        let SchedulingDecision::Run { pid, .. } = log.decision else {
+            continue;
+        };
+        if log.processes[&pid].group == group {
+            *used.entry(log.start_time / 10).or_insert(0) += log.run_time();
+        }
+    }
+    used
+}
+
+#[test]
+pub fn quota() {
+    let logs = run(0);
+    assert!(used_by_period(&logs, 1).values().all(|used| *used <= 2));
+    assert_eq!(logs.last().unwrap().decision, SchedulingDecision::Done);
+
+    // the processor sleeps while the only runnable process is throttled
+    let throttled = logs
+        .iter()
+        .find(|log| matches!(log.decision, SchedulingDecision::Sleep(_)))
+        .unwrap();
+    assert_eq!(throttled.throttled(), [Pid::new(2)]);
+    assert_eq!(throttled.start_time, 7);
+    assert_eq!(throttled.idle_time(), 3);
+    assert!(throttled
+        .processes
+        .values()
+        .all(|process| process.state != ProcessState::Ready));
+
+    // and the process runs again at the beginning of every period
+    let runs = logs
+        .iter()
+        .filter(|log| log.decision.to_string() == "Run 2 for 2 slices")
+        .map(|log| log.start_time)
+        .collect::<Vec<usize>>();
+    assert_eq!(runs, [4, 10, 20, 30, 40, 50]);
+
+    // the group is throttled when it has used its quota and released
+    // at the next period
+    let events = logs
+        .iter()
+        .flat_map(|log| log.throttle_events.iter().copied())
+        .collect::<Vec<ThrottleEvent>>();
+    let throttled_at = |time| ThrottleEvent::Throttled {
+        group: 1,
+        time,
+        used: 2,
+    };
+    let released_at = |time| ThrottleEvent::Released {
+        group: 1,
+        time,
+        used: 2,
+    };
+    assert_eq!(
+        events[..4],
+        [
+            throttled_at(6),
+            released_at(10),
+            throttled_at(12),
+            released_at(20)
+        ]
+    );
+
+    // every event is shown once, in the log of the decision that follows it
+    let shown = logs
+        .iter()
+        .filter(|log| {
+            log.to_string()
+                .contains("Group 1 throttled at 6 after using 2")
+        })
+        .count();
+    assert_eq!(shown, 1);
+}
+
+#[test]
+pub fn other_groups_run() {
+    let logs = run(30);
+    assert!(used_by_period(&logs, 1).values().all(|used| *used <= 2));
+
+    // process 3 uses the time left by the throttled group
+    let busy = logs
+        .iter()
+        .take_while(|log| log.start_time < 30)
+        .all(|log| !matches!(log.decision, SchedulingDecision::Sleep(_)));
+    assert!(busy);
+    assert!(logs.iter().any(|log| !log.throttled().is_empty()
+        && matches!(log.decision, SchedulingDecision::Run { pid, .. } if pid == 3)));
+}
+
+#[test]
+pub fn command_line() {
+    let args = ["run", "-s", "fs", "--quota", "1=5/20", "w.toml"];
+    let Ok(Command::Run { config, .. }) = parse_args(args.map(String::from)) else {
+        panic!("run command not parsed");
+    };
+    assert_eq!(config.groups.bandwidth[&1], Bandwidth::new(5, 20));
+    assert!(parse_args(["run", "--quota", "1=5", "w.toml"].map(String::from)).is_err());
+    assert!(parse_args(["run", "--quota", "1=0/20", "w.toml"].map(String::from)).is_err());
+}
+
+#[test]
+pub fn invariants_and_reference() {
+    for groups in [limited(), limited().bandwidth(2, Bandwidth::new(1, 4))] {
+        check_policy_with(
+            || scheduler(groups.clone()),
+            &policy_config(),
+            |workload| workload.ops.insert(0, Op::Group(1)),
+        );
+    }
+}
@@ -0,0 +1,160 @@
+use std::num::NonZeroUsize;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use processor::{GeneratorConfig, Log, Metrics, Options, Processor};
+use scheduler::{
+    rt_classes, Pid, RtPriority, SchedClass, Scheduler, SchedulerError, SchedulingDecision,
+    StopReason, SyscallResult,
+};
+
+use super::{check_policy_with, policy_config};
+use crate::workload::load;
+
+fn scheduler() -> impl Scheduler {
+    rt_classes(NonZeroUsize::new(3).unwrap(), 1)
+}
+
+fn fifo(priority: u8) -> SchedClass {
+    SchedClass::Fifo(RtPriority::new(priority))
+}
+
+fn rr(priority: u8) -> SchedClass {
+    SchedClass::RoundRobin(RtPriority::new(priority))
+}
+
+/// Process 1 becomes the most important process, forks the processes
+/// in their classes and lets them run.
+fn run_classes(classes: Vec<SchedClass>, execs: usize) -> Vec<Log> {
+    Processor::run(scheduler(), move |process| {
+        process.set_class(process.pid, fifo(99));
+        for class in classes {
+            process.fork_class(
+                move |process| {
+                    for _ in 0..execs {
+                        process.exec();
+                    }
+                },
+                0,
+                class,
+            );
+        }
+        process.sleep(100);
+    })
+}
+
+fn metrics(logs: &[Log], pid: usize) -> (usize, usize) {
+    let metrics = &Metrics::from_logs(logs).processes[pid - 1];
+    (metrics.first_run.unwrap(), metrics.completion.unwrap())
+}
+
+#[test]
+pub fn precedence() {
+    let logs = run_classes(vec![SchedClass::Other, fifo(10), rr(20)], 3);
+    let (other, _) = metrics(&logs, 2);
+    let (fifo, _) = metrics(&logs, 3);
+    let (rr, _) = metrics(&logs, 4);
+    assert!(rr < fifo);
+    assert!(fifo < other);
+}
+
+#[test]
+pub fn fifo_is_not_time_sliced() {
+    let logs = run_classes(vec![fifo(10), fifo(10)], 10);
+    let (_, first) = metrics(&logs, 2);
+    let (second, _) = metrics(&logs, 3);
+    assert!(first <= second);
+
+    // the round robin class takes turns at the same priority
+    let logs = run_classes(vec![rr(10), rr(10)], 10);
+    let (_, first) = metrics(&logs, 2);
+    let (second, _) = metrics(&logs, 3);
+    assert!(second < first);
+}
+
+#[test]
+pub fn fifo_keeps_the_processor() {
+    let workload = load(Path::new("workloads/rt_classes.toml")).unwrap();
+    let logs = workload.run(scheduler(), Options::default());
+
+    // the processes that run one after the other
+    let mut runs = vec![];
+    for log in logs.iter() {
+        if let SchedulingDecision::Run { pid, .. } = log.decision {
+            if runs.last() != Some(&pid) {
+                runs.push(pid);
+            }
+        }
+    }
+    assert_eq!(runs, [1, 2, 3, 4, 2, 4, 1].map(Pid::new));
+
+    // the FIFO process has run through the end of its timeslices
+    let expired = logs
+        .iter()
+        .filter(|log| {
+            matches!(log.decision, SchedulingDecision::Run { pid, .. } if pid == 3)
+                && matches!(log.stop_reason, Some((StopReason::Expired, _)))
+        })
+        .count();
+    assert_eq!(expired, 2);
+}
+
+#[test]
+pub fn set_class() {
+    let results = Arc::new(Mutex::new(vec![]));
+    let shared = results.clone();
+    let logs = Processor::run(scheduler(), move |process| {
+        let child_results = shared.clone();
+        let child = process.fork(
+            move |process| {
+                process.sleep(5);
+                let result = process.set_class(Pid::new(1), fifo(1));
+                child_results.lock().unwrap().push(result);
+            },
+            0,
+        );
+        let result = process.set_class(child, fifo(5));
+        shared.lock().unwrap().push(result);
+        let result = process.set_class(Pid::new(9), fifo(5));
+        shared.lock().unwrap().push(result);
+        process.sleep(10);
+    });
+    assert_eq!(
+        *results.lock().unwrap(),
+        [
+            SyscallResult::Success,
+            SyscallResult::InvalidPid,
+            SyscallResult::PermissionDenied
+        ]
+    );
+
+    let class = |log: &Log, pid| {
+        log.processes
+            .get(&Pid::new(pid))
+            .map(|process| process.ext.clone())
+    };
+    assert_eq!(class(&logs[0], 1).as_deref(), Some("OTHER"));
+    assert!(logs
+        .iter()
+        .any(|log| class(log, 2).as_deref() == Some("FIFO 5")));
+}
+
+#[test]
+pub fn rt_priority() {
+    assert_eq!(RtPriority::new(99).get(), 99);
+    for priority in [0, 100] {
+        assert_eq!(
+            RtPriority::try_new(priority),
+            Err(SchedulerError::InvalidRtPriority(priority))
+        );
+    }
+}
+
+#[test]
+pub fn invariants_and_reference() {
+    let config = GeneratorConfig {
+        class_chance: 20,
+        ..policy_config()
+    };
+    check_policy_with(scheduler, &config, |_| {});
+}
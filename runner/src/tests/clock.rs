@@ -0,0 +1,141 @@
+use std::num::NonZeroUsize;
+
+use processor::{GeneratorConfig, Options, Processor, Workload};
+use scheduler::{Clock, Pcb, Pid, ProcessState, SleepQueue};
+
+use super::scheduler;
+
+const SEEDS: u64 = 30;
+
+fn sleeper(pid: usize, wakeup: usize) -> Pcb {
+    let mut process = Pcb::new(Pid::new(pid), None, 0, ());
+    process.state = ProcessState::Waiting { event: None };
+    process.wakeup = wakeup;
+    process
+}
+
+#[test]
+pub fn accounting() {
+    let clock = Clock::default();
+    let mut process = Pcb::new(Pid::new(1), None, 0, ());
+    clock.run(&mut process, 3, true);
+    clock.run(&mut process, 2, false);
+    assert_eq!(process.timings, (0, 1, 4));
+}
+
+#[test]
+pub fn wake_in_order() {
+    let mut clock = Clock::default();
+    let mut sleepers = SleepQueue::default();
+    for process in [sleeper(2, 4), sleeper(3, 2), sleeper(4, 3)] {
+        sleepers.push(process, clock.now());
+    }
+    assert_eq!(
+        clock.next_wakeup(&sleepers),
+        Some(NonZeroUsize::new(2).unwrap())
+    );
+
+    clock.advance(3, None::<&mut Pcb>);
+    let woken = clock.wake(&mut sleepers);
+    assert_eq!(
+        woken
+            .iter()
+            .map(|process| (process.pid, process.state, process.timings.0))
+            .collect::<Vec<_>>(),
+        vec![
+            (Pid::new(3), ProcessState::Ready, 3),
+            (Pid::new(4), ProcessState::Ready, 3)
+        ]
+    );
+    assert_eq!(
+        clock.next_wakeup(&sleepers),
+        Some(NonZeroUsize::new(1).unwrap())
+    );
+}
+
+#[test]
+pub fn deferred_sleep() {
+    let mut clock = Clock::default();
+    let mut sleepers = SleepQueue::default();
+    sleepers.push(sleeper(2, 5), clock.now());
+    clock.sleep(5);
+    assert_eq!(clock.now(), 0);
+    assert!(clock.wake(&mut sleepers).is_empty());
+
+    clock.resume(None::<&mut Pcb>);
+    assert_eq!(clock.now(), 5);
+    assert_eq!(clock.wake(&mut sleepers)[0].timings.0, 5);
+    clock.resume(None::<&mut Pcb>);
+    assert_eq!(clock.now(), 5);
+}
+
+/// A process that is forked later than PID 1 has not existed for longer.
+#[test]
+pub fn children_younger_than_init() {
+    let config = GeneratorConfig {
+        processes: 6,
+        devices: 2,
+        io_chance: 30,
+        sleep_chance: 30,
+        ..GeneratorConfig::default()
+    };
+    for seed in 0..SEEDS {
+        let logs = Workload::generate(&config, seed).run(scheduler(), Options::default());
+        for (iteration, log) in logs.iter().enumerate() {
+            let Some(init) = log.processes.get(&Pid::new(1)) else {
+                continue;
+            };
+            for process in log.processes.values() {
+                assert!(
+                    process.timings.0 <= init.timings.0,
+                    "seed {seed}, iteration {}: {process}",
+                    iteration + 1
+                );
+            }
+        }
+    }
+}
+
+/// The iterations follow each other and last as long as the time they use.
+#[test]
+pub fn log_times() {
+    let config = GeneratorConfig {
+        processes: 6,
+        devices: 2,
+        io_chance: 30,
+        ..GeneratorConfig::default()
+    };
+    let options = Options { context_switch: 1 };
+    for seed in 0..SEEDS {
+        let logs = Workload::generate(&config, seed).run(scheduler(), options);
+        assert_eq!(logs[0].start_time, 0);
+        for (iteration, log) in logs.iter().enumerate() {
+            assert_eq!(
+                log.end_time - log.start_time,
+                log.elapsed(),
+                "seed {seed}, iteration {}",
+                iteration + 1
+            );
+            if let Some(next) = logs.get(iteration + 1) {
+                assert_eq!(log.end_time, next.start_time, "seed {seed}");
+            }
+        }
+    }
+}
+
+#[test]
+pub fn process_now() {
+    let mut times = vec![];
+    let logs = Processor::run(scheduler(), |process| {
+        times.push(process.now());
+        process.exec();
+        process.exec();
+        times.push(process.now());
+        process.sleep(4);
+        times.push(process.now());
+    });
+
+    // the sleep and exit system calls use one time unit
+    assert_eq!(times, [0, 2, 7]);
+    assert_eq!(logs.last().unwrap().start_time, 8);
+}
@@ -0,0 +1,87 @@
+use core::module_path;
+use std::num::NonZeroUsize;
+
+use function_name::named;
+use processor::{Log, Metrics, Options, Processor};
+use scheduler::{Pid, Scheduler};
+
+use super::{run, scheduler};
+
+fn workers(options: Options) -> Vec<Log> {
+    workers_with(scheduler(), options)
+}
+
+fn workers_with(scheduler: impl Scheduler + 'static, options: Options) -> Vec<Log> {
+    Processor::run_with_options(scheduler, options, |process| {
+        for _ in 0..2 {
+            process.fork(
+                |process| {
+                    for _ in 0..10 {
+                        process.exec();
+                    }
+                },
+                0,
+            );
+        }
+        for _ in 0..10 {
+            process.exec();
+        }
+    })
+}
+
+#[test]
+#[named]
+pub fn free_switch() {
+    let logs = workers(Options::default());
+
+    let metrics = Metrics::from_logs(&logs);
+    assert!(metrics.context_switches > 0);
+    assert_eq!(metrics.context_switch_time, 0);
+    assert_eq!(metrics.time, metrics.cpu_time + metrics.idle_time);
+    run(
+        module_path!().split("::").last().unwrap(),
+        function_name!(),
+        &logs,
+    );
+}
+
+#[test]
+#[named]
+pub fn costly_switch() {
+    let logs = workers(Options { context_switch: 2 });
+
+    let metrics = Metrics::from_logs(&logs);
+    let free = Metrics::from_logs(&workers(Options::default()));
+    assert_eq!(metrics.context_switches, free.context_switches);
+    assert_eq!(metrics.context_switch_time, 2 * metrics.context_switches);
+    assert_eq!(metrics.time, free.time + metrics.context_switch_time);
+    // the scheduler lets the switch time pass for the processes
+    for log in logs.iter() {
+        if let Some(init) = log.processes.get(&Pid::new(1)) {
+            assert_eq!(init.timings.0, log.start_time);
+        }
+    }
+    run(
+        module_path!().split("::").last().unwrap(),
+        function_name!(),
+        &logs,
+    );
+}
+
+#[test]
+pub fn throughput_falls_with_the_timeslice() {
+    let throughput = [8, 4, 2, 1]
+        .into_iter()
+        .map(|timeslice| {
+            let scheduler = scheduler::round_robin(NonZeroUsize::new(timeslice).unwrap(), 0);
+            let metrics =
+                Metrics::from_logs(&workers_with(scheduler, Options { context_switch: 2 }));
+            metrics.cpu_time as f64 / metrics.time as f64
+        })
+        .collect::<Vec<f64>>();
+
+    assert!(
+        throughput.windows(2).all(|pair| pair[0] > pair[1]),
+        "{throughput:?}"
+    );
+}
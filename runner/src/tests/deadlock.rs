@@ -131,3 +131,28 @@ pub fn wait_3() {
         &logs,
     );
 }
+
+#[test]
+#[named]
+pub fn cycle() {
+    let logs = Processor::run(scheduler(), |process| {
+        process.fork(
+            |process| {
+                process.will_signal(1);
+                process.exec();
+                process.wait(2);
+                process.signal(1);
+            },
+            0,
+        );
+        process.will_signal(2);
+        process.wait(1);
+        process.signal(2);
+    });
+
+    run(
+        module_path!().split("::").last().unwrap(),
+        function_name!(),
+        &logs,
+    );
+}
@@ -0,0 +1,63 @@
+use core::module_path;
+use function_name::named;
+use processor::{Metrics, Processor};
+
+use super::{run, scheduler};
+
+#[test]
+#[named]
+pub fn single_request() {
+    let logs = Processor::run(scheduler(), |process| {
+        for _ in 0..5 {
+            process.exec();
+        }
+        process.io(0, 10);
+        for _ in 0..5 {
+            process.exec();
+        }
+    });
+
+    let metrics = Metrics::from_logs(&logs);
+    assert_eq!(metrics.devices.len(), 1);
+    assert_eq!(metrics.devices[0].requests, 1);
+    assert_eq!(metrics.devices[0].busy_time, 10);
+    run(
+        module_path!().split("::").last().unwrap(),
+        function_name!(),
+        &logs,
+    );
+}
+
+#[test]
+#[named]
+pub fn contention() {
+    let logs = Processor::run(scheduler(), |process| {
+        for _ in 0..2 {
+            process.fork(
+                |process| {
+                    for _ in 0..2 {
+                        process.io(0, 5);
+                        process.exec();
+                    }
+                },
+                0,
+            );
+        }
+        process.io(1, 3);
+        process.io(0, 5);
+        for _ in 0..30 {
+            process.exec();
+        }
+    });
+
+    let metrics = Metrics::from_logs(&logs);
+    assert_eq!(metrics.devices[0].requests, 5);
+    assert_eq!(metrics.devices[0].busy_time, 25);
+    assert_eq!(metrics.devices[1].busy_time, 3);
+    assert!(metrics.devices[0].utilisation <= 1.0);
+    run(
+        module_path!().split("::").last().unwrap(),
+        function_name!(),
+        &logs,
+    );
+}
@@ -0,0 +1,43 @@
+use std::fs;
+
+use processor::{compare_engines, FuzzCase, GeneratorConfig, Options, Workload};
+
+use super::scheduler;
+
+const SEEDS: u64 = 50;
+
+#[test]
+pub fn generated_workloads() {
+    let config = GeneratorConfig {
+        processes: 8,
+        devices: 2,
+        io_chance: 20,
+        events: 6,
+        priority_spread: 5,
+        ..GeneratorConfig::default()
+    };
+    for seed in 0..SEEDS {
+        let workload = Workload::generate(&config, seed);
+        if let Err(divergence) = compare_engines(&workload, Options::default(), scheduler) {
+            panic!("seed {seed}: {divergence}");
+        }
+    }
+}
+
+#[test]
+pub fn fuzz_corpus() {
+    let mut inputs = fs::read_dir("../fuzz/corpus/differential")
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<_>>();
+    inputs.sort();
+    assert!(!inputs.is_empty());
+
+    for input in inputs {
+        let case = FuzzCase::from_bytes(&fs::read(&input).unwrap());
+        let scheduler = || case.new_scheduler();
+        if let Err(divergence) = compare_engines(&case.workload, case.options, scheduler) {
+            panic!("{} ({}): {divergence}", input.display(), case.scheduler);
+        }
+    }
+}
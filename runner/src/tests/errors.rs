@@ -0,0 +1,119 @@
+use std::num::NonZeroUsize;
+
+use processor::{compare_engines, Op, Options, Processor, Workload};
+use scheduler::{
+    round_robin, PanicReason, Pid, Process, Scheduler, SchedulerError, SchedulingDecision,
+    StopReason, Syscall, SyscallResult, ThrottleEvent,
+};
+
+use super::scheduler;
+
+/// Answers a fork with [`SyscallResult::Success`] instead of a PID,
+/// after the first process has been created.
+struct BrokenFork<S: Scheduler> {
+    scheduler: S,
+    forks: usize,
+}
+
+impl<S: Scheduler> Scheduler for BrokenFork<S> {
+    fn next(&mut self) -> SchedulingDecision {
+        self.scheduler.next()
+    }
+
+    fn stop(&mut self, reason: StopReason) -> SyscallResult {
+        let result = self.scheduler.stop(reason);
+        if let StopReason::Syscall {
+            syscall: Syscall::Fork(_),
+            ..
+        } = reason
+        {
+            self.forks += 1;
+            if self.forks > 1 {
+                return SyscallResult::Success;
+            }
+        }
+        result
+    }
+
+    fn list(&mut self) -> Vec<&dyn Process> {
+        self.scheduler.list()
+    }
+
+    fn context_switch(&mut self, time: usize) {
+        self.scheduler.context_switch(time)
+    }
+
+    fn io_completion(&mut self, pid: Pid, wakeup: usize) {
+        self.scheduler.io_completion(pid, wakeup)
+    }
+
+    fn throttle_events(&mut self) -> Vec<ThrottleEvent> {
+        self.scheduler.throttle_events()
+    }
+}
+
+#[test]
+pub fn invalid_pid() {
+    assert_eq!(Pid::try_new(0), Err(SchedulerError::InvalidPid(0)));
+    assert_eq!(Pid::try_new(3), Ok(Pid::new(3)));
+}
+
+#[test]
+pub fn expired_without_process() {
+    let mut scheduler = round_robin(NonZeroUsize::new(3).unwrap(), 1);
+    assert_eq!(
+        scheduler.stop(StopReason::Expired),
+        SyscallResult::NoRunningProcess
+    );
+}
+
+#[test]
+pub fn zero_remaining() {
+    let workload = Workload::new(
+        0,
+        vec![
+            Op::Exec(4),
+            Op::Fork(Workload::new(0, vec![Op::Exec(2), Op::Sleep(1)])),
+            Op::Yield,
+            Op::Exec(5),
+        ],
+    );
+    let logs = workload.run(
+        round_robin(NonZeroUsize::new(3).unwrap(), 0),
+        Options::default(),
+    );
+    assert_eq!(logs.last().unwrap().decision, SchedulingDecision::Done);
+}
+
+#[test]
+pub fn unexpected_fork_result() {
+    let broken = || BrokenFork {
+        scheduler: scheduler(),
+        forks: 0,
+    };
+    let mut forked = None;
+    let logs = Processor::run(broken(), |process| {
+        forked = Some(process.try_fork(|process| process.exec(), 0));
+        process.exec();
+    });
+
+    assert_eq!(forked, Some(None));
+    let last = logs.last().unwrap();
+    assert_eq!(
+        last.decision,
+        SchedulingDecision::Panic(PanicReason::ContractViolation)
+    );
+    assert!(matches!(
+        last.error,
+        Some(SchedulerError::UnexpectedResult {
+            reason: StopReason::Syscall {
+                syscall: Syscall::Fork(0),
+                ..
+            },
+            result: SyscallResult::Success,
+        })
+    ));
+
+    let workload = Workload::new(0, vec![Op::Fork(Workload::new(0, vec![])), Op::Exec(1)]);
+    assert!(compare_engines(&workload, Options::default(), broken).is_ok());
+}
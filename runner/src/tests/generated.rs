@@ -0,0 +1,73 @@
+use processor::{GeneratorConfig, Options, Workload};
+use scheduler::SchedulingDecision;
+
+use super::scheduler;
+use crate::workload::{format, parse};
+
+const SEEDS: u64 = 50;
+
+#[test]
+pub fn reproducible() {
+    let config = GeneratorConfig::default();
+    assert_eq!(
+        Workload::generate(&config, 1),
+        Workload::generate(&config, 1)
+    );
+    assert_ne!(
+        Workload::generate(&config, 1),
+        Workload::generate(&config, 2)
+    );
+}
+
+#[test]
+pub fn toml_round_trip() {
+    let config = GeneratorConfig {
+        devices: 2,
+        io_chance: 30,
+        priority_spread: 10,
+        class_chance: 20,
+        reserved_chance: 20,
+        deadlock: true,
+        ..GeneratorConfig::default()
+    };
+    for seed in 0..SEEDS {
+        let workload = Workload::generate(&config, seed);
+        assert_eq!(parse(&format(&workload)), Ok(workload), "seed {seed}");
+    }
+}
+
+#[test]
+pub fn deadlock_free() {
+    let config = GeneratorConfig {
+        processes: 8,
+        devices: 2,
+        io_chance: 20,
+        events: 6,
+        priority_spread: 5,
+        ..GeneratorConfig::default()
+    };
+    for seed in 0..SEEDS {
+        let logs = Workload::generate(&config, seed).run(scheduler(), Options::default());
+        assert_eq!(
+            logs.last().map(|log| log.decision),
+            Some(SchedulingDecision::Done),
+            "seed {seed}"
+        );
+    }
+}
+
+#[test]
+pub fn deadlocking() {
+    let config = GeneratorConfig {
+        deadlock: true,
+        ..GeneratorConfig::default()
+    };
+    for seed in 0..SEEDS {
+        let logs = Workload::generate(&config, seed).run(scheduler(), Options::default());
+        assert_eq!(
+            logs.last().map(|log| log.decision),
+            Some(SchedulingDecision::Deadlock),
+            "seed {seed}"
+        );
+    }
+}
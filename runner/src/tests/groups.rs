@@ -0,0 +1,147 @@
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+
+use processor::{Log, Metrics, Op, Processor};
+use scheduler::{
+    fair_share, round_robin, GroupConfig, Pid, Scheduler, StopReason, Syscall, SyscallResult,
+};
+
+use super::{check_policy_with, policy_config};
+use crate::cli::{parse_args, Command};
+use crate::workload::parse;
+
+fn scheduler(groups: GroupConfig) -> impl Scheduler {
+    fair_share(NonZeroUsize::new(3).unwrap(), 1, groups)
+}
+
+fn weight(weight: usize) -> NonZeroUsize {
+    NonZeroUsize::new(weight).unwrap()
+}
+
+/// The logs until the first process exits.
+fn until_exit(logs: &[Log]) -> &[Log] {
+    let exit = logs
+        .iter()
+        .position(|log| {
+            matches!(
+                log.stop_reason,
+                Some((
+                    StopReason::Syscall {
+                        syscall: Syscall::Exit,
+                        ..
+                    },
+                    _
+                ))
+            )
+        })
+        .unwrap();
+    &logs[..exit]
+}
+
+#[test]
+pub fn weights() {
+    let groups = GroupConfig::default()
+        .weight(1, weight(100))
+        .weight(2, weight(300));
+    let logs = Processor::run(scheduler(groups), |process| {
+        for group in [1, 2] {
+            process.fork(
+                move |process| {
+                    process.set_group(process.pid, group);
+                    for _ in 0..60 {
+                        process.exec();
+                    }
+                },
+                0,
+            );
+        }
+        process.sleep(200);
+    });
+
+    let metrics = Metrics::from_logs(until_exit(&logs));
+    let cpu_time = |group| {
+        metrics
+            .groups
+            .iter()
+            .find(|metrics| metrics.group == group)
+            .unwrap()
+            .cpu_time
+    };
+    assert!(cpu_time(1) > 0);
+    assert!(cpu_time(2) >= 2 * cpu_time(1));
+    assert!(cpu_time(2) <= 4 * cpu_time(1));
+    assert!(Metrics::from_logs(&logs)
+        .to_string()
+        .contains("GROUP\tCPU\tSHARE"));
+}
+
+#[test]
+pub fn inherited_group() {
+    // the round robin scheduler ignores the groups, but stores them
+    let schedulers: [Box<dyn Scheduler>; 2] = [
+        Box::new(scheduler(GroupConfig::default())),
+        Box::new(round_robin(NonZeroUsize::new(3).unwrap(), 1)),
+    ];
+    for scheduler in schedulers {
+        let results = Arc::new(Mutex::new(vec![]));
+        let shared = results.clone();
+        let logs = Processor::run(scheduler, move |process| {
+            let child_results = shared.clone();
+            let child = process.fork(
+                move |process| {
+                    process.set_group(process.pid, 1);
+                    process.fork(|process| process.exec(), 0);
+                    let result = process.set_group(Pid::new(1), 1);
+                    child_results.lock().unwrap().push(result);
+                },
+                0,
+            );
+            process.sleep(20);
+            let result = process.set_group(child, 2);
+            shared.lock().unwrap().push(result);
+        });
+        assert_eq!(
+            *results.lock().unwrap(),
+            [SyscallResult::PermissionDenied, SyscallResult::InvalidPid]
+        );
+
+        let group = |pid| {
+            logs.iter()
+                .rev()
+                .find_map(|log| log.processes.get(&Pid::new(pid)))
+                .unwrap()
+                .group
+        };
+        assert_eq!(group(1), 0);
+        assert_eq!(group(2), 1);
+        assert_eq!(group(3), 1);
+    }
+}
+
+#[test]
+pub fn workload_groups() {
+    let workload = parse("ops = [{ group = 2 }, { exec = 1 }]").unwrap();
+    assert_eq!(workload.ops[0], Op::Group(2));
+
+    let args = ["run", "-s", "fs", "--weight", "1=300", "w.toml"];
+    let Ok(Command::Run { config, .. }) = parse_args(args.map(String::from)) else {
+        panic!("run command not parsed");
+    };
+    assert_eq!(config.name, "fair-share");
+    assert_eq!(config.groups.weight_of(1), weight(300));
+    assert!(parse_args(["run", "--weight", "1", "w.toml"].map(String::from)).is_err());
+}
+
+#[test]
+pub fn invariants_and_reference() {
+    for groups in [
+        GroupConfig::default().weight(1, weight(300)),
+        GroupConfig::default().weight(1, weight(50)),
+    ] {
+        check_policy_with(
+            || scheduler(groups.clone()),
+            &policy_config(),
+            |workload| workload.ops.insert(0, Op::Group(1)),
+        );
+    }
+}
@@ -0,0 +1,15 @@
+use processor::check_invariants;
+
+use super::scheduler;
+
+const SEEDS: u64 = 200;
+const STEPS: usize = 500;
+
+#[test]
+pub fn random_syscalls() {
+    for seed in 0..SEEDS {
+        if let Err(violation) = check_invariants(scheduler(), seed, STEPS) {
+            panic!("{violation}");
+        }
+    }
+}
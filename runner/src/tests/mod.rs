@@ -1,19 +1,36 @@
-#[cfg(not(any(feature = "priority-queue", feature = "cfs")))]
-use scheduler::round_robin;
 use scheduler::Scheduler;
 
 use std::env;
 use std::fs;
+use std::num::NonZeroUsize;
 
 use processor::format_logs;
 use processor::Log;
-use std::num::NonZeroUsize;
+use processor::{check_invariants, compare_engines, GeneratorConfig, Options, Workload};
 
+use crate::config::{parse, SchedulerConfig};
+
+mod aging;
+mod bandwidth;
+mod classes;
+mod clock;
+mod context_switch;
 mod deadlock;
+mod devices;
+mod differential;
+mod errors;
+mod generated;
+mod groups;
+mod invariants;
+mod observer;
 mod panic;
+mod rate_monotonic;
+mod reservations;
 mod simple;
 mod wait_and_signal;
+mod wait_queues;
 mod workers;
+mod workload;
 
 fn write_logs(folder: &str, name: &str, logs: &str) {
     let (timeslice, remaining, cpu_slices) = arguments();
@@ -36,7 +53,7 @@ fn read_logs(folder: &str, name: &str) -> String {
 }
 
 fn run(folder: &str, name: &str, logs: &[Log]) {
-    let output = format_logs(&logs);
+    let output = format_logs(logs);
 
     if env::var("WRITE_OUTPUT").is_ok() {
         write_logs(folder, name, &output);
@@ -49,38 +66,104 @@ fn run(folder: &str, name: &str, logs: &[Log]) {
     }
 }
 
+/// The tested configuration, read from the `TIMESLICE`, `REMAINING` and
+/// `CPU_SLICES` environment variables, the ones that are not set keep their
+/// default value.
+fn config() -> SchedulerConfig {
+    let mut config = SchedulerConfig::default();
+    let var = |name| {
+        env::var(name)
+            .ok()
+            .map(|value: String| parse(name, &value).unwrap())
+    };
+    if let Some(timeslice) = var("TIMESLICE") {
+        config.timeslice = NonZeroUsize::new(timeslice).expect("TIMESLICE must not be 0");
+    }
+    if let Some(remaining) = var("REMAINING") {
+        config.remaining = remaining;
+    }
+    if let Some(cpu_slices) = var("CPU_SLICES") {
+        config.cpu_slices = cpu_slices;
+    }
+    config
+}
+
+/// The number of seeds that [`check_policy`] tries.
+const POLICY_SEEDS: u64 = 50;
+
+/// Check the invariants of a scheduling policy with random system calls and
+/// compare the two engines on generated workloads, with a new scheduler from
+/// `factory` for every run.
+fn check_policy<S: Scheduler + 'static>(factory: impl Fn() -> S) {
+    check_policy_with(factory, &policy_config(), |_| {});
+}
+
+/// The workloads generated by [`check_policy`].
+fn policy_config() -> GeneratorConfig {
+    GeneratorConfig {
+        processes: 8,
+        devices: 2,
+        io_chance: 20,
+        events: 6,
+        priority_spread: 5,
+        ..GeneratorConfig::default()
+    }
+}
+
+/// Like [`check_policy`], with the workloads generated from `config` and
+/// every one of them changed by `prepare` before it runs.
+fn check_policy_with<S: Scheduler + 'static>(
+    factory: impl Fn() -> S,
+    config: &GeneratorConfig,
+    prepare: impl Fn(&mut Workload),
+) {
+    for seed in 0..POLICY_SEEDS {
+        if let Err(violation) = check_invariants(factory(), seed, 500) {
+            panic!("{violation}");
+        }
+
+        let mut workload = Workload::generate(config, seed);
+        prepare(&mut workload);
+        if let Err(divergence) = compare_engines(&workload, Options::default(), &factory) {
+            panic!("seed {seed}: {divergence}");
+        }
+    }
+}
+
 fn arguments() -> (usize, usize, usize) {
-    let timeslice = env::var("TIMESLICE")
-        .unwrap_or("3".to_string())
-        .parse::<usize>()
-        .unwrap();
-    let remaining = env::var("REMAINING")
-        .unwrap_or("1".to_string())
-        .parse::<usize>()
-        .unwrap();
-    let cpu_slices = env::var("CPU_SLICES")
-        .unwrap_or("10".to_string())
-        .parse::<usize>()
-        .unwrap();
-    (timeslice, remaining, cpu_slices)
+    let config = config();
+    (config.timeslice.get(), config.remaining, config.cpu_slices)
 }
 
 #[cfg(feature = "round-robin")]
 static SCHEDULER: &str = "round-robin";
-#[cfg(feature = "round-robin")]
-fn scheduler() -> impl Scheduler {
-    let (timeslice, remaining, cpu_slices) = arguments();
 
-    println!("Timeslice {timeslice}\nRemaining {remaining}\nCPU slices: {cpu_slices}");
-    round_robin(NonZeroUsize::new(timeslice).unwrap(), remaining)
-}
+#[cfg(feature = "priority-queue")]
+static SCHEDULER: &str = "priority-queue";
 
 #[cfg(not(any(feature = "round-robin", feature = "priority-queue", feature = "cfs")))]
 static SCHEDULER: &str = "no-scheduler";
-#[cfg(not(any(feature = "round-robin", feature = "priority-queue", feature = "cfs")))]
+
+/// The [`SchedulerConfig`] name of the tested scheduler, the round robin
+/// one without a scheduler feature.
+fn scheduler_name() -> &'static str {
+    match SCHEDULER {
+        "no-scheduler" => "round-robin",
+        name => name,
+    }
+}
+
 fn scheduler() -> impl Scheduler {
-    let (timeslice, remaining, cpu_slices) = arguments();
+    let config = config();
 
-    println!("Timeslice {timeslice}\nRemaining {remaining}\nCPU slices: {cpu_slices}");
-    round_robin(NonZeroUsize::new(timeslice).unwrap(), remaining)
+    println!(
+        "Timeslice {}\nRemaining {}\nCPU slices: {}",
+        config.timeslice, config.remaining, config.cpu_slices
+    );
+    SchedulerConfig {
+        name: scheduler_name().to_string(),
+        ..config
+    }
+    .build()
+    .unwrap()
 }
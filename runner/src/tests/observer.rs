@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use processor::{GeneratorConfig, Options, Workload};
+use scheduler::{Event, Observed, Pid, ProcessState, SchedulingDecision};
+
+use super::scheduler;
+
+const SEEDS: u64 = 20;
+
+/// The hooks describe the same simulation as the logs, which list the
+/// processes after every decision.
+#[test]
+pub fn replay_logs() {
+    let config = GeneratorConfig {
+        processes: 6,
+        devices: 2,
+        io_chance: 20,
+        ..GeneratorConfig::default()
+    };
+    for seed in 0..SEEDS {
+        let events = Arc::new(Mutex::new(Vec::<Event>::new()));
+        let mut observed = Observed::new(scheduler());
+        observed.attach(events.clone());
+        let logs = Workload::generate(&config, seed).run(observed, Options::default());
+        let events = events.lock().unwrap();
+
+        let mut states = HashMap::<Pid, ProcessState>::new();
+        let mut queued = HashMap::<Pid, ProcessState>::new();
+        let mut decisions = vec![];
+        let mut stops = vec![];
+        let mut checked = 0;
+        for event in events
+            .iter()
+            .chain([Event::Decision(SchedulingDecision::Done)].iter())
+        {
+            // the processes are listed after a decision, before the next stop
+            if matches!(event, Event::Decision(_) | Event::Stop { .. }) && checked < decisions.len()
+            {
+                let listed = logs[checked]
+                    .processes
+                    .iter()
+                    .map(|(pid, info)| (*pid, info.state))
+                    .collect::<HashMap<Pid, ProcessState>>();
+                assert_eq!(states, listed, "seed {seed}, iteration {}", checked + 1);
+                checked += 1;
+            }
+            match *event {
+                Event::Decision(decision) => decisions.push(decision),
+                Event::Stop { reason, result } => stops.push((reason, result)),
+                Event::StateChange { pid, from, to } => {
+                    assert_eq!(states.get(&pid).copied(), from, "seed {seed}");
+                    match to {
+                        Some(to) => states.insert(pid, to),
+                        None => states.remove(&pid),
+                    };
+                }
+                Event::Enqueue { pid, state } => {
+                    assert_eq!(queued.insert(pid, state), None, "seed {seed}");
+                }
+                Event::Dequeue { pid, state } => {
+                    assert_eq!(queued.remove(&pid), Some(state), "seed {seed}");
+                }
+            }
+        }
+        decisions.pop();
+
+        assert_eq!(
+            decisions,
+            logs.iter().map(|log| log.decision).collect::<Vec<_>>(),
+            "seed {seed}"
+        );
+        assert_eq!(
+            stops[1..],
+            logs.iter()
+                .filter_map(|log| log.stop_reason)
+                .collect::<Vec<_>>(),
+            "seed {seed}"
+        );
+    }
+}
@@ -1,7 +1,21 @@
 use function_name::named;
 use processor::Processor;
+use scheduler::{PanicReason, Pid, Scheduler, SchedulingDecision, SyscallResult};
 
-use super::{run, scheduler};
+use super::{config, run, scheduler, scheduler_name};
+use crate::config::SchedulerConfig;
+
+/// The tested scheduler, with orphans adopted by PID 1.
+fn adopting_scheduler() -> impl Scheduler {
+    let config = config();
+    SchedulerConfig {
+        name: scheduler_name().to_string(),
+        adopt_orphans: true,
+        ..config
+    }
+    .build()
+    .unwrap()
+}
 
 #[test]
 #[named]
@@ -64,3 +78,54 @@ pub fn wait() {
         &logs,
     );
 }
+
+#[test]
+pub fn survivors() {
+    let logs = Processor::run(scheduler(), |process| {
+        for _ in 0..2 {
+            process.fork(|process| process.sleep(5), 0);
+        }
+    });
+
+    let last = logs.last().unwrap();
+    assert_eq!(
+        last.decision,
+        SchedulingDecision::Panic(PanicReason::InitExited { alive: 2 })
+    );
+    assert_eq!(last.survivors(), [Pid::new(2), Pid::new(3)]);
+}
+
+#[test]
+#[named]
+#[cfg_attr(
+    any(feature = "priority-queue", feature = "cfs"),
+    ignore = "only the round robin scheduler adopts orphans"
+)]
+pub fn adopted() {
+    let mut adopted = None;
+    let logs = Processor::run(adopting_scheduler(), |process| {
+        process.fork(
+            |process| {
+                process.fork(
+                    |process| {
+                        process.sleep(5);
+                        process.exec();
+                    },
+                    0,
+                );
+            },
+            0,
+        );
+        process.sleep(2);
+        // process 3 is an orphan, adopted by process 1
+        adopted = Some(process.set_priority(Pid::new(3), 1));
+    });
+
+    assert_eq!(adopted, Some(SyscallResult::Success));
+    assert_eq!(logs.last().unwrap().decision, SchedulingDecision::Done);
+    run(
+        module_path!().split("::").last().unwrap(),
+        function_name!(),
+        &logs,
+    );
+}
@@ -0,0 +1,201 @@
+use std::num::NonZeroUsize;
+use std::path::Path;
+
+use processor::{
+    liu_layland_bound, Analysis, GeneratorConfig, Options, PeriodicTask, Processor, TaskSet,
+    Workload,
+};
+use scheduler::{edf, rate_monotonic, Pid, Reservation, SchedulingDecision};
+
+use super::{check_policy_with, policy_config, POLICY_SEEDS};
+use crate::cli::{parse_args, Command};
+use crate::compare::{compare_periodic, format_periodic_table};
+use crate::task_set::load;
+
+fn timeslice(timeslice: usize) -> NonZeroUsize {
+    NonZeroUsize::new(timeslice).unwrap()
+}
+
+fn task(runtime: usize, period: usize) -> PeriodicTask {
+    PeriodicTask::new(Reservation::new(runtime, period, period))
+}
+
+#[test]
+pub fn shortest_period_first() {
+    // process 2 has the shortest period, it forks the tasks before they run
+    let logs = Processor::run(rate_monotonic(timeslice(3), 1), |process| {
+        process.fork_reserved(
+            |process| {
+                for period in [20, 10] {
+                    process.fork_reserved(
+                        |process| {
+                            for _ in 0..3 {
+                                process.exec();
+                            }
+                        },
+                        0,
+                        Reservation::new(4, period, period),
+                    );
+                }
+            },
+            0,
+            Reservation::new(1, 1, 1),
+        );
+        process.sleep(30);
+    });
+    let mut runs = logs
+        .iter()
+        .filter_map(|log| match log.decision {
+            SchedulingDecision::Run { pid, .. } if pid != 1 && pid != 2 => Some(pid),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    runs.dedup();
+    // the second task has the shorter period
+    assert_eq!(runs, [Pid::new(4), Pid::new(3)]);
+    assert_eq!(
+        logs.iter()
+            .find_map(|log| log.processes.get(&Pid::new(4)).map(|pcb| pcb.ext.clone())),
+        Some("4/10/10".to_string())
+    );
+}
+
+#[test]
+pub fn liu_layland() {
+    assert_eq!(liu_layland_bound(1), 1.0);
+    assert!((liu_layland_bound(3) - 0.7798).abs() < 0.0001);
+    assert!(liu_layland_bound(100) > 2f64.ln());
+    assert!(liu_layland_bound(100) < liu_layland_bound(10));
+}
+
+#[test]
+pub fn response_time_analysis() {
+    let task_set = TaskSet {
+        tasks: vec![task(1, 4), task(2, 6), task(3, 12)],
+        horizon: 48,
+    };
+    let analysis = Analysis::rate_monotonic(&task_set, 0);
+    assert_eq!(analysis.response_times, [Some(1), Some(3), Some(10)]);
+    // above the bound, but every deadline is met
+    assert!(!analysis.within_bound());
+    assert!(analysis.schedulable());
+
+    let blocked = Analysis::rate_monotonic(&task_set, 2);
+    assert_eq!(blocked.response_times, [Some(3), Some(6), Some(12)]);
+    let blocked = Analysis::rate_monotonic(&task_set, 3);
+    assert_eq!(blocked.response_times, [Some(4), None, None]);
+}
+
+#[test]
+pub fn simulation_within_prediction() {
+    let task_sets = [
+        vec![task(1, 4), task(2, 6), task(3, 12)],
+        vec![task(2, 5), task(3, 10), task(4, 20)],
+        vec![task(1, 3), task(1, 5), task(2, 15)],
+    ];
+    for tasks in task_sets {
+        let task_set = TaskSet { tasks, horizon: 60 };
+        for timeslice in [1, 2, 3].map(timeslice) {
+            let analysis = Analysis::rate_monotonic(&task_set, timeslice.get() - 1);
+            let run = task_set.run(rate_monotonic(timeslice, 1), Options::default());
+            let comparisons = analysis.compare(&run);
+            assert!(
+                comparisons.iter().all(|comparison| comparison.holds()),
+                "{comparisons:?}"
+            );
+            if analysis.schedulable() {
+                assert_eq!(run.missed(), 0);
+            }
+        }
+    }
+    // the jobs of the first two tasks are released together, so they reach
+    // their worst case; the last task is forked after them
+    let task_set = TaskSet {
+        tasks: vec![task(1, 4), task(2, 6), task(3, 12)],
+        horizon: 48,
+    };
+    let run = task_set.run(rate_monotonic(timeslice(1), 1), Options::default());
+    let comparisons = Analysis::rate_monotonic(&task_set, 0).compare(&run);
+    assert!(comparisons[..2]
+        .iter()
+        .all(|comparison| comparison.observed == comparison.predicted));
+}
+
+#[test]
+pub fn unschedulable_task_set() {
+    // 97% of the processor, which only EDF can use
+    let task_set = TaskSet {
+        tasks: vec![task(2, 5), task(4, 7)],
+        horizon: 90,
+    };
+    let analysis = Analysis::rate_monotonic(&task_set, 0);
+    assert_eq!(analysis.response_times, [Some(2), None]);
+    assert!(!analysis.schedulable());
+
+    let run = task_set.run(rate_monotonic(timeslice(1), 1), Options::default());
+    assert!(run.tasks(&task_set)[1].missed > 0);
+    let run = task_set.run(edf(timeslice(1), 1), Options::default());
+    assert_eq!(run.missed(), 0);
+}
+
+#[test]
+pub fn command_line() {
+    let args = ["periodic", "-s", "rm", "-t", "1", "workloads/tasks.toml"];
+    let Ok(Command::Periodic {
+        configs,
+        options,
+        tasks,
+    }) = parse_args(args.map(String::from))
+    else {
+        panic!("periodic command not parsed");
+    };
+    assert_eq!(configs[0].name, "rate-monotonic");
+
+    let task_set = load(&tasks).unwrap();
+    let rows = compare_periodic(&task_set, &configs, options).unwrap();
+    assert!(rows[0].analysis.is_some());
+    let table = format_periodic_table(&rows);
+    assert!(table.lines().next().unwrap().ends_with("PREDICTED"));
+    // the first two tasks are predicted exactly, the last one overruns
+    let predicted = table
+        .lines()
+        .skip(1)
+        .map(|line| line.split_whitespace().last().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(predicted, ["2", "5", "18"]);
+
+    let task_set = load(Path::new("workloads/tasks.toml")).unwrap();
+    let configs = parse_args(["periodic", "-s", "edf", "t.toml"].map(String::from));
+    let Ok(Command::Periodic { configs, .. }) = configs else {
+        panic!("periodic command not parsed");
+    };
+    let rows = compare_periodic(&task_set, &configs, options).unwrap();
+    assert!(rows[0].analysis.is_none());
+    assert!(format_periodic_table(&rows)
+        .lines()
+        .nth(1)
+        .unwrap()
+        .ends_with('-'));
+}
+
+#[test]
+pub fn invariants_and_reference() {
+    let config = GeneratorConfig {
+        reserved_chance: 30,
+        ..policy_config()
+    };
+    check_policy_with(|| rate_monotonic(timeslice(3), 1), &config, |_| {});
+
+    // the generated workloads have periodic processes, listed with their reservation
+    let periodic = (0..POLICY_SEEDS).any(|seed| {
+        Workload::generate(&config, seed)
+            .run(rate_monotonic(timeslice(3), 1), Options::default())
+            .iter()
+            .any(|log| {
+                log.processes
+                    .values()
+                    .any(|process| !process.ext.is_empty())
+            })
+    });
+    assert!(periodic);
+}
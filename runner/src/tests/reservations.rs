@@ -0,0 +1,192 @@
+use std::num::NonZeroUsize;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use processor::{GeneratorConfig, Options, PeriodicTask, Processor, TaskSet, Workload};
+use scheduler::{
+    edf, reservations, round_robin, Pid, Reservation, Scheduler, SchedulingDecision, SyscallResult,
+};
+
+use super::{check_policy_with, policy_config, POLICY_SEEDS};
+use crate::cli::{parse_args, Command};
+use crate::compare::{compare_periodic, format_periodic_table};
+use crate::task_set::{load, parse};
+
+fn timeslice(timeslice: usize) -> NonZeroUsize {
+    NonZeroUsize::new(timeslice).unwrap()
+}
+
+fn task(runtime: usize, period: usize) -> PeriodicTask {
+    PeriodicTask::new(Reservation::new(runtime, period, period))
+}
+
+/// The number of missed deadlines of every task.
+fn missed<S: Scheduler + 'static>(scheduler: S, task_set: &TaskSet) -> Vec<usize> {
+    let run = task_set.run(scheduler, Options::default());
+    assert!(run.denied.is_empty());
+    run.tasks(task_set).iter().map(|task| task.missed).collect()
+}
+
+/// Process 1 forks processes that reserve 50%, 40% and 20% of the
+/// processor, then another one that reserves 20% after they have exited.
+fn forks<S: Scheduler + 'static>(scheduler: S) -> Vec<Option<Pid>> {
+    let results = Arc::new(Mutex::new(vec![]));
+    let shared = results.clone();
+    Processor::run(scheduler, move |process| {
+        for runtime in [5, 4, 2] {
+            let child = process.fork_reserved(
+                |process| {
+                    process.exec();
+                    process.sleep(20);
+                },
+                0,
+                Reservation::new(runtime, 10, 10),
+            );
+            shared.lock().unwrap().push(child);
+        }
+        process.sleep(50);
+        let child = process.fork_reserved(|process| process.exec(), 0, Reservation::new(2, 10, 10));
+        shared.lock().unwrap().push(child);
+    });
+    let results = results.lock().unwrap().clone();
+    results
+}
+
+#[test]
+pub fn admission_control() {
+    let pid = |pid| Some(Pid::new(pid));
+    assert_eq!(
+        forks(reservations(timeslice(3), 1)),
+        [pid(2), pid(3), None, pid(4)]
+    );
+    // without servers every reservation is admitted
+    assert_eq!(
+        forks(edf(timeslice(3), 1)),
+        [pid(2), pid(3), pid(4), pid(5)]
+    );
+    assert_eq!(
+        forks(round_robin(timeslice(3), 1)),
+        [pid(2), pid(3), pid(4), pid(5)]
+    );
+}
+
+#[test]
+pub fn denied_fork_is_logged() {
+    let logs = Processor::run(reservations(timeslice(3), 1), |process| {
+        process.fork_reserved(|process| process.sleep(5), 0, Reservation::new(3, 4, 4));
+        process.fork_reserved(|process| process.sleep(5), 0, Reservation::new(1, 2, 2));
+        process.sleep(10);
+    });
+    assert!(logs
+        .iter()
+        .any(|log| matches!(log.stop_reason, Some((_, SyscallResult::AdmissionDenied)))));
+    assert_eq!(logs.last().unwrap().decision, SchedulingDecision::Done);
+}
+
+#[test]
+pub fn budget_replenishment() {
+    // the process declares 2 time units every 10, but executes 7
+    let logs = Processor::run(reservations(timeslice(3), 1), |process| {
+        process.fork_reserved(
+            |process| {
+                for _ in 0..7 {
+                    process.exec();
+                }
+            },
+            0,
+            Reservation::new(2, 10, 10),
+        );
+        process.sleep(30);
+    });
+    let runs = logs
+        .iter()
+        .filter_map(|log| match log.decision {
+            SchedulingDecision::Run { pid, timeslice } if pid == 2 => {
+                Some((timeslice.get(), log.processes[&pid].ext.clone()))
+            }
+            _ => None,
+        })
+        .collect::<Vec<(usize, String)>>();
+    assert_eq!(
+        runs,
+        [
+            (2, "2/10/10 deadline 11 budget 2".to_string()),
+            (2, "2/10/10 deadline 21 budget 2".to_string()),
+            (2, "2/10/10 deadline 31 budget 2".to_string()),
+            (2, "2/10/10 deadline 41 budget 2".to_string()),
+        ]
+    );
+}
+
+#[test]
+pub fn deadlines_are_met() {
+    // the tasks use 90% of the processor
+    let task_set = TaskSet {
+        tasks: vec![task(2, 5), task(3, 10), task(4, 20)],
+        horizon: 60,
+    };
+    assert_eq!(missed(edf(timeslice(1), 1), &task_set), [0, 0, 0]);
+    assert_eq!(missed(reservations(timeslice(1), 1), &task_set), [0, 0, 0]);
+    assert!(missed(round_robin(timeslice(1), 1), &task_set)[0] > 0);
+}
+
+#[test]
+pub fn servers_isolate_overruns() {
+    // the last task executes twice the runtime it declares
+    let task_set = load(Path::new("workloads/tasks.toml")).unwrap();
+    for timeslice in [1, 2, 3].map(timeslice) {
+        let edf = missed(edf(timeslice, 1), &task_set);
+        assert!(edf[0] + edf[1] > 0);
+        let servers = missed(reservations(timeslice, 1), &task_set);
+        assert_eq!(servers[..2], [0, 0]);
+        assert!(servers[2] > 0);
+    }
+}
+
+#[test]
+pub fn command_line() {
+    let args = ["periodic", "-s", "cbs", "-t", "1,2", "workloads/tasks.toml"];
+    let Ok(Command::Periodic {
+        configs,
+        options,
+        tasks,
+    }) = parse_args(args.map(String::from))
+    else {
+        panic!("periodic command not parsed");
+    };
+    assert_eq!(configs.len(), 2);
+    assert_eq!(configs[0].name, "reservation");
+
+    let task_set = load(&tasks).unwrap();
+    assert_eq!(task_set.tasks[1].reservation, Reservation::new(3, 8, 10));
+    assert_eq!(task_set.tasks[2].execution, 8);
+    let rows = compare_periodic(&task_set, &configs, options).unwrap();
+    let table = format_periodic_table(&rows);
+    assert_eq!(table.lines().count(), 2 * task_set.tasks.len() + 1);
+
+    assert!(parse_args(["periodic", "-f", "csv", "t.toml"].map(String::from)).is_err());
+    assert!(parse("tasks = []").is_err());
+    assert!(parse("horizon = 10\ntasks = [{ runtime = 3, period = 2 }]").is_err());
+    assert!(parse("horizon = 10\ntasks = [{ runtime = 1, priod = 2 }]").is_err());
+}
+
+#[test]
+pub fn invariants_and_reference() {
+    let config = GeneratorConfig {
+        reserved_chance: 30,
+        ..policy_config()
+    };
+    check_policy_with(|| edf(timeslice(3), 1), &config, |_| {});
+    check_policy_with(|| reservations(timeslice(3), 1), &config, |_| {});
+
+    // the generated reservations fit, no fork is denied
+    for seed in 0..POLICY_SEEDS {
+        let logs = Workload::generate(&config, seed)
+            .run(reservations(timeslice(3), 1), Options::default());
+        assert_eq!(
+            logs.last().map(|log| log.decision),
+            Some(SchedulingDecision::Done),
+            "seed {seed}"
+        );
+    }
+}
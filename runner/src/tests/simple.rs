@@ -158,3 +158,29 @@ pub fn fork_wait_sleep_signal() {
         &logs,
     );
 }
+
+#[test]
+#[named]
+pub fn fork_yield() {
+    let logs = Processor::run(scheduler(), |process| {
+        process.fork(
+            |process| {
+                for _ in 0..4 {
+                    process.exec();
+                    process.yield_now();
+                }
+            },
+            0,
+        );
+        for _ in 0..4 {
+            process.exec();
+            process.yield_now();
+        }
+    });
+
+    run(
+        module_path!().split("::").last().unwrap(),
+        function_name!(),
+        &logs,
+    );
+}
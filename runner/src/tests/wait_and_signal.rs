@@ -111,3 +111,99 @@ pub fn senders() {
         &logs,
     );
 }
+
+#[test]
+#[named]
+pub fn signal_one() {
+    let mut woken = vec![];
+    let logs = Processor::run(scheduler(), |process| {
+        for _ in 0..2 {
+            process.fork(
+                |process| {
+                    process.wait(1);
+                    for _ in 0..3 {
+                        process.exec();
+                    }
+                },
+                0,
+            );
+        }
+        for _ in 0..5 {
+            process.exec();
+        }
+        woken.push(process.signal_one(1));
+        process.sleep(10);
+        woken.push(process.signal_one(1));
+        woken.push(process.signal_one(1));
+        process.sleep(10);
+    });
+
+    assert_eq!(woken, vec![1, 1, 0]);
+    run(
+        module_path!().split("::").last().unwrap(),
+        function_name!(),
+        &logs,
+    );
+}
+
+#[test]
+#[named]
+pub fn signal_latched() {
+    let mut woken = vec![];
+    let logs = Processor::run(scheduler(), |process| {
+        process.fork(
+            |process| {
+                for _ in 0..10 {
+                    process.exec();
+                }
+                process.wait(1);
+                process.wait(1);
+            },
+            0,
+        );
+        woken.push(process.signal_latched(1));
+        process.sleep(20);
+        woken.push(process.signal_latched(1));
+        process.sleep(10);
+    });
+
+    assert_eq!(woken, vec![0, 1]);
+    run(
+        module_path!().split("::").last().unwrap(),
+        function_name!(),
+        &logs,
+    );
+}
+
+#[test]
+#[named]
+pub fn wait_latched() {
+    let logs = Processor::run(scheduler(), |process| {
+        process.signal_latched(1);
+        // the event is latched, so the process keeps the processor
+        process.fork(
+            |process| {
+                process.wait(1);
+                for _ in 0..3 {
+                    process.exec();
+                }
+            },
+            0,
+        );
+        process.fork(
+            |process| {
+                for _ in 0..3 {
+                    process.exec();
+                }
+            },
+            0,
+        );
+        process.sleep(20);
+    });
+
+    run(
+        module_path!().split("::").last().unwrap(),
+        function_name!(),
+        &logs,
+    );
+}
@@ -0,0 +1,47 @@
+use scheduler::{Pcb, Pid, ProcessState, WaitQueues};
+
+fn waiting(processes: &[(usize, usize)]) -> WaitQueues {
+    let mut waiting = WaitQueues::default();
+    for (pid, event) in processes.iter() {
+        waiting.push(*event, Pcb::new(Pid::new(*pid), None, 0, ()));
+    }
+    waiting
+}
+
+fn pids<'a>(processes: impl Iterator<Item = &'a Pcb>) -> Vec<Pid> {
+    processes.map(|process| process.pid).collect()
+}
+
+#[test]
+pub fn wake_in_order() {
+    let mut waiting = waiting(&[(2, 1), (3, 2), (4, 1), (5, 1)]);
+    assert_eq!(
+        waiting.waiters(1).next().unwrap().state,
+        ProcessState::Waiting { event: Some(1) }
+    );
+
+    let woken = waiting.wake(1, 2);
+    assert_eq!(pids(woken.iter()), [Pid::new(2), Pid::new(4)]);
+    assert!(woken
+        .iter()
+        .all(|process| process.state == ProcessState::Ready));
+    assert_eq!(pids(waiting.wake(1, usize::MAX).iter()), [Pid::new(5)]);
+    assert!(waiting.wake(1, usize::MAX).is_empty());
+    assert!(waiting.wake(7, usize::MAX).is_empty());
+}
+
+#[test]
+pub fn query() {
+    let mut waiting = waiting(&[(2, 3), (3, 1), (4, 3)]);
+    assert_eq!(waiting.len(), 3);
+    assert_eq!(waiting.events(), [1, 3]);
+    assert_eq!(pids(waiting.waiters(3)), [Pid::new(2), Pid::new(4)]);
+    assert_eq!(waiting.waiting_for(Pid::new(3)), Some(1));
+    assert_eq!(waiting.waiting_for(Pid::new(5)), None);
+
+    waiting.wake(1, 1);
+    assert_eq!(waiting.events(), [3]);
+    assert_eq!(waiting.waiting_for(Pid::new(3)), None);
+    waiting.wake(3, 2);
+    assert!(waiting.is_empty());
+}
@@ -1,6 +1,8 @@
 use core::module_path;
 use function_name::named;
 use processor::Processor;
+use scheduler::{Pid, SyscallResult};
+use std::sync::{Arc, Mutex};
 
 use super::{run, scheduler};
 
@@ -163,3 +165,68 @@ pub fn sleeper() {
         &logs,
     );
 }
+
+#[test]
+#[named]
+pub fn priorities() {
+    let mut results = vec![];
+    let worker_results = Arc::new(Mutex::new(vec![]));
+    let shared = worker_results.clone();
+    let logs = Processor::run(scheduler(), |process| {
+        let worker = process.fork(
+            move |process| {
+                for _ in 0..5 {
+                    process.exec();
+                }
+                let mut results = shared.lock().unwrap();
+                results.push(process.nice(-5));
+                results.push(process.set_priority(Pid::new(3), 1));
+                results.push(process.set_priority(Pid::new(1), 1));
+                results.push(process.set_priority(Pid::new(42), 1));
+                process.signal_latched(1);
+                process.signal_latched(2);
+            },
+            0,
+        );
+        let sibling = process.fork(
+            |process| {
+                for _ in 0..10 {
+                    process.exec();
+                }
+                process.wait(2);
+            },
+            0,
+        );
+        results.push(process.set_priority(worker, 3));
+        results.push(process.nice(2));
+        for _ in 0..10 {
+            process.exec();
+        }
+        // the worker may have a lower priority than the others
+        process.wait(1);
+        results.push(process.set_priority(sibling + 10, 3));
+    });
+
+    assert_eq!(
+        results,
+        vec![
+            SyscallResult::Success,
+            SyscallResult::Success,
+            SyscallResult::InvalidPid
+        ]
+    );
+    assert_eq!(
+        *worker_results.lock().unwrap(),
+        vec![
+            SyscallResult::Success,
+            SyscallResult::PermissionDenied,
+            SyscallResult::PermissionDenied,
+            SyscallResult::InvalidPid
+        ]
+    );
+    run(
+        module_path!().split("::").last().unwrap(),
+        function_name!(),
+        &logs,
+    );
+}
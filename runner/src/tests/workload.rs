@@ -0,0 +1,108 @@
+use std::path::Path;
+
+use processor::{Options, Processor};
+
+use super::scheduler;
+use crate::cli::{parse_args, Command};
+use crate::compare::{compare, format_table};
+use crate::config::SCHEDULERS;
+use crate::workload::{load, parse};
+
+#[test]
+pub fn example_matches_closure() {
+    let workload = load(Path::new("workloads/example.toml")).unwrap();
+    let logs = workload.run(scheduler(), Options::default());
+
+    let reference = Processor::run(scheduler(), |process| {
+        for _ in 0..4 {
+            process.exec();
+        }
+        process.fork(
+            |process| {
+                process.exec();
+                process.exec();
+                process.wait(1);
+            },
+            0,
+        );
+        process.sleep(10);
+        process.signal(1);
+        process.exec();
+    });
+
+    assert_eq!(logs, reference);
+}
+
+#[test]
+pub fn invalid_workloads() {
+    assert!(parse("ops = [{ exec = -1 }]").is_err());
+    assert!(parse("ops = [{ exec = 1, sleep = 2 }]").is_err());
+    assert!(parse("ops = [{ io = { device = 0 } }]").is_err());
+    assert!(parse("ops = [\"sleep\"]").is_err());
+    assert!(parse("priority = 0\nname = \"init\"").is_err());
+}
+
+#[test]
+pub fn command_line() {
+    let args = [
+        "run",
+        "-s",
+        "rr",
+        "--timeslice",
+        "5",
+        "-r",
+        "2",
+        "workload.toml",
+    ];
+    let Ok(Command::Run {
+        config, workload, ..
+    }) = parse_args(args.map(String::from))
+    else {
+        panic!("run command not parsed");
+    };
+    assert_eq!(config.name, "round-robin");
+    assert_eq!(config.timeslice.get(), 5);
+    assert_eq!(config.remaining, 2);
+    assert_eq!(workload, Path::new("workload.toml"));
+
+    assert!(parse_args(["run".to_string()]).is_err());
+    assert!(parse_args(["export", "-f", "svg", "w.toml"].map(String::from)).is_err());
+    assert!(parse_args(["run", "-t", "0", "w.toml"].map(String::from)).is_err());
+}
+
+#[test]
+pub fn compare_grid() {
+    let args = [
+        "compare",
+        "-t",
+        "2,3",
+        "-r",
+        "1,2",
+        "workloads/example.toml",
+    ];
+    let Ok(Command::Compare {
+        configs, options, ..
+    }) = parse_args(args.map(String::from))
+    else {
+        panic!("compare command not parsed");
+    };
+    assert_eq!(configs.len(), SCHEDULERS.len() * 4);
+
+    let workload = load(Path::new("workloads/example.toml")).unwrap();
+    let rows = compare(&workload, &configs, options).unwrap();
+    for row in rows.iter() {
+        let metrics = &row.metrics;
+        assert!(row.decision.is_some());
+        assert_eq!(
+            metrics.time,
+            metrics.cpu_time + metrics.idle_time + metrics.context_switch_time
+        );
+        for process in metrics.processes.iter() {
+            if let (Some(turnaround), Some(response)) = (process.turnaround(), process.response()) {
+                assert!(response <= turnaround);
+                assert!(process.cpu_time + process.waiting_time <= turnaround);
+            }
+        }
+    }
+    assert_eq!(format_table(&rows).lines().count(), rows.len() + 1);
+}
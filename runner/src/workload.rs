@@ -0,0 +1,223 @@
+//! Loading and writing workloads as TOML files.
+//!
+//! A workload file describes the instructions of the process with PID 1.
+//! Every instruction is a table with one key, except `"yield"`, and the
+//! processes forked are described by nested workloads. The scheduling
+//! classes are written like they are logged, `"OTHER"`, `"FIFO 10"` or `"RR 10"`,
+//! and the deadline of a reservation is its period if it is not given:
+//!
+//! ```toml
+//! ops = [
+//!     { exec = 4 },
+//!     { fork = { priority = 0, ops = [{ exec = 2 }, { wait = 1 }] } },
+//!     { fork_class = { class = "RR 10", priority = 0, ops = [{ exec = 3 }] } },
+//!     { set_class = "FIFO 20" },
+//!     { fork_reserved = { runtime = 2, period = 10, priority = 0, ops = [{ exec = 2 }] } },
+//!     { sleep = 10 },
+//!     { signal = 1 },
+//!     { io = { device = 0, duration = 5 } },
+//!     "yield",
+//! ]
+//! ```
+
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use processor::{Op, Workload};
+use scheduler::{Reservation, RtPriority, SchedClass};
+use toml::{Table, Value};
+
+/// Read and parse a workload file.
+pub fn load(path: &Path) -> Result<Workload, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|error| format!("cannot read {}: {error}", path.display()))?;
+    parse(&text).map_err(|error| format!("{}: {error}", path.display()))
+}
+
+/// Parse the text of a workload file.
+pub fn parse(text: &str) -> Result<Workload, String> {
+    let table = text.parse::<Table>().map_err(|error| error.to_string())?;
+    workload(&table, "workload")
+}
+
+/// Write a workload in the format read by [`parse`].
+pub fn format(workload: &Workload) -> String {
+    let mut s = format!("priority = {}\nops = [\n", workload.priority);
+    for op in workload.ops.iter() {
+        writeln!(s, "    {},", format_op(op)).unwrap();
+    }
+    s.push_str("]\n");
+    s
+}
+
+fn format_op(op: &Op) -> String {
+    match op {
+        Op::Exec(units) => format!("{{ exec = {units} }}"),
+        Op::Fork(workload) => format!("{{ fork = {{ {} }} }}", format_fork(workload)),
+        Op::ForkClass { class, workload } => format!(
+            "{{ fork_class = {{ class = \"{class}\", {} }} }}",
+            format_fork(workload)
+        ),
+        Op::ForkReserved {
+            reservation,
+            workload,
+        } => format!(
+            "{{ fork_reserved = {{ runtime = {}, deadline = {}, period = {}, {} }} }}",
+            reservation.runtime,
+            reservation.deadline,
+            reservation.period,
+            format_fork(workload)
+        ),
+        Op::Sleep(time) => format!("{{ sleep = {time} }}"),
+        Op::Wait(event) => format!("{{ wait = {event} }}"),
+        Op::Signal(event) => format!("{{ signal = {event} }}"),
+        Op::SignalOne(event) => format!("{{ signal_one = {event} }}"),
+        Op::SignalLatched(event) => format!("{{ signal_latched = {event} }}"),
+        Op::Io { device, duration } => {
+            format!("{{ io = {{ device = {device}, duration = {duration} }} }}")
+        }
+        Op::Nice(priority) => format!("{{ nice = {priority} }}"),
+        Op::Group(group) => format!("{{ group = {group} }}"),
+        Op::SetClass(class) => format!("{{ set_class = \"{class}\" }}"),
+        Op::Yield => "\"yield\"".to_string(),
+    }
+}
+
+/// The priority and the instructions of a forked process.
+fn format_fork(workload: &Workload) -> String {
+    let ops = workload
+        .ops
+        .iter()
+        .map(format_op)
+        .collect::<Vec<String>>()
+        .join(", ");
+    format!("priority = {}, ops = [{ops}]", workload.priority)
+}
+
+fn workload(table: &Table, path: &str) -> Result<Workload, String> {
+    let mut workload = Workload::default();
+    for (key, value) in table.iter() {
+        match key.as_str() {
+            "priority" => workload.priority = integer(value, &format!("{path}.priority"))?,
+            "ops" => {
+                let ops = value
+                    .as_array()
+                    .ok_or_else(|| format!("{path}.ops must be an array"))?;
+                for (index, value) in ops.iter().enumerate() {
+                    workload
+                        .ops
+                        .push(op(value, &format!("{path}.ops[{index}]"))?);
+                }
+            }
+            _ => return Err(format!("unknown key {path}.{key}")),
+        }
+    }
+    Ok(workload)
+}
+
+fn op(value: &Value, path: &str) -> Result<Op, String> {
+    if value.as_str() == Some("yield") {
+        return Ok(Op::Yield);
+    }
+
+    let table = value
+        .as_table()
+        .filter(|table| table.len() == 1)
+        .ok_or_else(|| format!("{path} must be \"yield\" or a table with one instruction"))?;
+    let (name, value) = table.iter().next().unwrap();
+    let path = format!("{path}.{name}");
+
+    match name.as_str() {
+        "exec" => Ok(Op::Exec(integer(value, &path)?)),
+        "fork" => {
+            let table = value
+                .as_table()
+                .ok_or_else(|| format!("{path} must be a workload table"))?;
+            Ok(Op::Fork(workload(table, &path)?))
+        }
+        "fork_class" => {
+            let mut table = value
+                .as_table()
+                .ok_or_else(|| format!("{path} must be a workload table with a class"))?
+                .clone();
+            let class = table
+                .remove("class")
+                .ok_or_else(|| format!("{path}.class is missing"))?;
+            Ok(Op::ForkClass {
+                class: class_of(&class, &format!("{path}.class"))?,
+                workload: workload(&table, &path)?,
+            })
+        }
+        "fork_reserved" => {
+            let mut table = value
+                .as_table()
+                .ok_or_else(|| format!("{path} must be a workload table with a reservation"))?
+                .clone();
+            let mut field = |key: &str| {
+                table
+                    .remove(key)
+                    .map(|value| integer::<usize>(&value, &format!("{path}.{key}")))
+                    .transpose()
+            };
+            let runtime = field("runtime")?.ok_or_else(|| format!("{path}.runtime is missing"))?;
+            let period = field("period")?.ok_or_else(|| format!("{path}.period is missing"))?;
+            let deadline = field("deadline")?.unwrap_or(period);
+            let reservation = Reservation::try_new(runtime, deadline, period)
+                .map_err(|error| format!("{path}: {error}"))?;
+            Ok(Op::ForkReserved {
+                reservation,
+                workload: workload(&table, &path)?,
+            })
+        }
+        "sleep" => Ok(Op::Sleep(integer(value, &path)?)),
+        "wait" => Ok(Op::Wait(integer(value, &path)?)),
+        "signal" => Ok(Op::Signal(integer(value, &path)?)),
+        "signal_one" => Ok(Op::SignalOne(integer(value, &path)?)),
+        "signal_latched" => Ok(Op::SignalLatched(integer(value, &path)?)),
+        "io" => {
+            let field = |key: &str| {
+                value
+                    .get(key)
+                    .ok_or_else(|| format!("{path}.{key} is missing"))
+                    .and_then(|value| integer(value, &format!("{path}.{key}")))
+            };
+            Ok(Op::Io {
+                device: field("device")?,
+                duration: field("duration")?,
+            })
+        }
+        "nice" => Ok(Op::Nice(integer(value, &path)?)),
+        "group" => Ok(Op::Group(integer(value, &path)?)),
+        "set_class" => Ok(Op::SetClass(class_of(value, &path)?)),
+        _ => Err(format!("unknown instruction {path}")),
+    }
+}
+
+fn class_of(value: &Value, path: &str) -> Result<SchedClass, String> {
+    let rt_priority = |priority: &str| {
+        priority
+            .parse()
+            .ok()
+            .and_then(|priority| RtPriority::try_new(priority).ok())
+    };
+    let class = match value.as_str() {
+        Some("OTHER") => Some(SchedClass::Other),
+        Some(text) => match text.split_once(' ') {
+            Some(("FIFO", priority)) => rt_priority(priority).map(SchedClass::Fifo),
+            Some(("RR", priority)) => rt_priority(priority).map(SchedClass::RoundRobin),
+            _ => None,
+        },
+        None => None,
+    };
+    class.ok_or_else(|| {
+        format!("{path} must be \"OTHER\", \"FIFO <priority>\" or \"RR <priority>\"")
+    })
+}
+
+pub(crate) fn integer<T: TryFrom<i64>>(value: &Value, path: &str) -> Result<T, String> {
+    value
+        .as_integer()
+        .and_then(|value| T::try_from(value).ok())
+        .ok_or_else(|| format!("{path} must be an integer in range"))
+}
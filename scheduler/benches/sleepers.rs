@@ -0,0 +1,113 @@
+//! Thousands of sleeping processes: the sleep queue against a linear scan,
+//! and the round robin scheduler putting them to sleep and waking them up.
+//!
+//! cargo bench -p scheduler --bench sleepers
+
+use std::collections::{HashMap, VecDeque};
+use std::num::NonZeroUsize;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use scheduler::{
+    Pcb, Pid, Scheduler, SchedulingDecision, SleepQueue, StopReason, Syscall, SyscallResult,
+};
+
+const SLEEPERS: [usize; 3] = [1_000, 5_000, 20_000];
+
+/// Wakeup times that are spread and repeat, like the ones of a workload.
+fn wakeups(count: usize) -> Vec<usize> {
+    (0..count)
+        .map(|index| (index * 7_919) % (count / 2) + 1)
+        .collect()
+}
+
+fn sleeper(pid: usize, wakeup: usize) -> Pcb {
+    let mut process = Pcb::new(Pid::new(pid), None, 0, ());
+    process.wakeup = wakeup;
+    process
+}
+
+/// Add every sleeper, then wake them up in order.
+fn sleep_queue(wakeups: &[usize]) -> usize {
+    let mut sleepers = SleepQueue::default();
+    for (index, wakeup) in wakeups.iter().enumerate() {
+        sleepers.push(sleeper(index + 1, *wakeup), 0);
+    }
+    let mut woken = 0;
+    while let Some(now) = sleepers.next_wakeup() {
+        while sleepers.pop_due(now).is_some() {
+            woken += 1;
+        }
+    }
+    woken
+}
+
+/// The same with a scan of the whole queue for every wakeup.
+fn linear_scan(wakeups: &[usize]) -> usize {
+    let mut sleepers = VecDeque::new();
+    for (index, wakeup) in wakeups.iter().enumerate() {
+        sleepers.push_back(sleeper(index + 1, *wakeup));
+    }
+    let mut woken = 0;
+    while let Some(now) = sleepers.iter().map(|process| process.wakeup).min() {
+        let before = sleepers.len();
+        sleepers.retain(|process| process.wakeup > now);
+        woken += before - sleepers.len();
+    }
+    woken
+}
+
+/// Fork the sleepers from PID 1, let each one sleep once and run the
+/// scheduler until every process has exited.
+fn round_robin(wakeups: &[usize]) -> usize {
+    let mut scheduler = scheduler::round_robin(NonZeroUsize::new(3).unwrap(), 1);
+    let mut sleeps = HashMap::new();
+    let mut forked = 0;
+    scheduler.stop(StopReason::syscall(Syscall::Fork(0)));
+    let mut decisions = 0;
+    loop {
+        decisions += 1;
+        match scheduler.next() {
+            SchedulingDecision::Run { pid, .. } => {
+                let syscall = if pid == 1 && forked < wakeups.len() {
+                    Syscall::Fork(0)
+                } else if pid == 1 && !sleeps.contains_key(&pid) {
+                    // PID 1 exits after the other processes
+                    sleeps.insert(pid, 0);
+                    Syscall::Sleep(wakeups.len() + 1)
+                } else {
+                    match sleeps.insert(pid, 0) {
+                        Some(time) if time > 0 => Syscall::Sleep(time),
+                        _ => Syscall::Exit,
+                    }
+                };
+                if let SyscallResult::Pid(child) = scheduler.stop(StopReason::syscall(syscall)) {
+                    sleeps.insert(child, wakeups[forked]);
+                    forked += 1;
+                }
+            }
+            SchedulingDecision::Sleep(_) => {}
+            _ => return decisions,
+        }
+    }
+}
+
+fn sleepers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sleepers");
+    group.sample_size(10);
+    for count in SLEEPERS {
+        let wakeups = wakeups(count);
+        group.bench_with_input(BenchmarkId::new("sleep_queue", count), &wakeups, |b, w| {
+            b.iter(|| sleep_queue(black_box(w)))
+        });
+        group.bench_with_input(BenchmarkId::new("linear_scan", count), &wakeups, |b, w| {
+            b.iter(|| linear_scan(black_box(w)))
+        });
+        group.bench_with_input(BenchmarkId::new("round_robin", count), &wakeups, |b, w| {
+            b.iter(|| round_robin(black_box(w)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, sleepers);
+criterion_main!(benches);
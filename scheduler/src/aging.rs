@@ -0,0 +1,94 @@
+//! Aging of the ready processes and starvation detection, for the policies
+//! that take priorities into account.
+
+use crate::pcb::{Pcb, PcbExt};
+
+/// Raises the effective priority of the ready processes with the time they
+/// have [waited](Pcb::waited) since they were last scheduled, and flags the
+/// processes that have waited for too long.
+///
+/// A larger priority is a more important process.
+///
+/// ## Example
+///
+/// ```rust
+/// use scheduler::{Aged, Aging, Pcb, Pid};
+///
+/// let aging = Aging::new(2, Some(4));
+/// let mut process = Pcb::new(Pid::new(2), None, 1, Aged::default());
+/// process.waited = 5;
+///
+/// assert_eq!(aging.priority(&process), 11);
+/// assert!(aging.starving(&process));
+/// aging.update(&mut process);
+/// assert_eq!(process.ext.to_string(), "effective=11 waited=5 starving");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Aging {
+    /// The priority added for every time unit that a process waits, 0
+    /// keeps the priorities unchanged.
+    pub rate: u8,
+
+    /// A process that waits for more than this many time units without
+    /// being scheduled is starving.
+    pub starvation: Option<usize>,
+}
+
+impl Aging {
+    pub fn new(rate: u8, starvation: Option<usize>) -> Aging {
+        Aging { rate, starvation }
+    }
+
+    /// The priority of the process, raised with the time it has waited.
+    pub fn priority<Ext: PcbExt>(&self, process: &Pcb<Ext>) -> i8 {
+        let boost = process.waited.saturating_mul(self.rate.into());
+        let boost = i8::try_from(boost).unwrap_or(i8::MAX);
+        process.priority.saturating_add(boost)
+    }
+
+    /// Whether the process has waited for more than the starvation threshold.
+    pub fn starving<Ext: PcbExt>(&self, process: &Pcb<Ext>) -> bool {
+        self.starvation
+            .is_some_and(|threshold| process.waited > threshold)
+    }
+
+    /// Copy the effective priority and the starvation of the process to its
+    /// extension, that shows them in [`Process::extra`](crate::Process::extra).
+    pub fn update(&self, process: &mut Pcb<Aged>) {
+        process.ext = Aged {
+            effective: self.priority(process),
+            waited: process.waited,
+            starving: self.starving(process),
+        };
+    }
+}
+
+/// The aging details of a process, shown as
+/// `effective=<priority> waited=<time>`, followed by `starving` if it is.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Aged {
+    /// The priority raised with the time the process has waited.
+    pub effective: i8,
+
+    /// The time the process has waited since it was last scheduled.
+    pub waited: usize,
+
+    /// Whether the process has waited for more than the starvation threshold.
+    pub starving: bool,
+}
+
+impl std::fmt::Display for Aged {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "effective={} waited={}", self.effective, self.waited)?;
+        if self.starving {
+            write!(f, " starving")?;
+        }
+        Ok(())
+    }
+}
+
+impl PcbExt for Aged {
+    fn extra(&self) -> String {
+        self.to_string()
+    }
+}
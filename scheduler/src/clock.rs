@@ -0,0 +1,117 @@
+//! The simulated time of a scheduler and the accounting of the process timings.
+
+use std::num::NonZeroUsize;
+
+use crate::pcb::{Pcb, PcbExt};
+use crate::scheduler::ProcessState;
+use crate::sleep_queue::SleepQueue;
+
+/// A monotonic clock that a policy advances when it is told how much time
+/// has passed, so that every policy accounts the time in the same way:
+///
+/// * every process adds the elapsed time to its total time;
+/// * the running process adds the time it has used to its execution time,
+///   except for the last time unit of a system call, which is counted as a
+///   system call;
+/// * a sleeping process wakes up when the clock reaches its
+///   [wakeup time](Pcb::wakeup), the [`SleepQueue`] adds the time it has
+///   slept, so the sleepers are not given to [`Clock::advance`];
+/// * a [`SchedulingDecision::Sleep`](crate::SchedulingDecision::Sleep)
+///   passes when the scheduler is called again, so the processes listed
+///   with the decision still show the time before it.
+///
+/// ## Example
+///
+/// ```rust
+/// use scheduler::{Clock, Pcb, Pid, ProcessState, SleepQueue};
+///
+/// let mut clock = Clock::default();
+/// let mut ready = Pcb::new(Pid::new(1), None, 0, ());
+/// let mut sleeper = Pcb::new(Pid::new(2), None, 0, ());
+/// sleeper.state = ProcessState::Waiting { event: None };
+/// sleeper.wakeup = clock.wakeup(2);
+/// let mut sleepers = SleepQueue::default();
+/// sleepers.push(sleeper, clock.now());
+///
+/// clock.advance(1, [&mut ready]);
+/// assert!(clock.wake(&mut sleepers).is_empty());
+/// clock.advance(1, [&mut ready]);
+/// let woken = clock.wake(&mut sleepers);
+/// assert_eq!((woken[0].state, woken[0].timings.0), (ProcessState::Ready, 2));
+/// assert_eq!(ready.timings.0, 2);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Clock {
+    now: usize,
+    sleeping: usize,
+}
+
+impl Clock {
+    /// The time that has passed since the first process was created.
+    pub fn now(&self) -> usize {
+        self.now
+    }
+
+    /// The wakeup time of a process that sleeps for `time` units from now.
+    pub fn wakeup(&self, time: usize) -> usize {
+        self.now + time
+    }
+
+    /// Let `time` units pass for the processes.
+    pub fn advance<'a, Ext: PcbExt + 'a>(
+        &mut self,
+        time: usize,
+        processes: impl IntoIterator<Item = &'a mut Pcb<Ext>>,
+    ) {
+        self.now += time;
+        for process in processes {
+            process.elapse(time);
+        }
+    }
+
+    /// Account the `time` used by the running process, the last time unit
+    /// is a system call if `syscall` is set.
+    pub fn run<Ext: PcbExt>(&self, process: &mut Pcb<Ext>, time: usize, syscall: bool) {
+        if syscall {
+            process.syscall();
+            process.execute(time.saturating_sub(1));
+        } else {
+            process.execute(time);
+        }
+    }
+
+    /// Remember that the processor sleeps for `time` units, the time
+    /// passes at the next call of [`Clock::resume`].
+    pub fn sleep(&mut self, time: usize) {
+        self.sleeping += time;
+    }
+
+    /// Let the time of the last [`Clock::sleep`] pass for the processes.
+    pub fn resume<'a, Ext: PcbExt + 'a>(
+        &mut self,
+        processes: impl IntoIterator<Item = &'a mut Pcb<Ext>>,
+    ) {
+        let time = std::mem::take(&mut self.sleeping);
+        if time > 0 {
+            self.advance(time, processes);
+        }
+    }
+
+    /// Remove the sleeping processes whose wakeup time has come, in the
+    /// order they wake up, and return them in the [`ProcessState::Ready`] state.
+    pub fn wake<Ext: PcbExt>(&self, sleepers: &mut SleepQueue<Ext>) -> Vec<Pcb<Ext>> {
+        let mut woken = vec![];
+        while let Some(mut process) = sleepers.pop_due(self.now) {
+            process.state = ProcessState::Ready;
+            woken.push(process);
+        }
+        woken
+    }
+
+    /// The time until the first of the sleeping processes wakes up.
+    pub fn next_wakeup<Ext: PcbExt>(&self, sleepers: &SleepQueue<Ext>) -> Option<NonZeroUsize> {
+        sleepers
+            .next_wakeup()
+            .and_then(|wakeup| NonZeroUsize::new(wakeup.saturating_sub(self.now)))
+    }
+}
@@ -0,0 +1,143 @@
+//! Process groups and their share of the processor, for the policies that
+//! are fair between groups.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+use std::num::NonZeroUsize;
+use std::str::FromStr;
+
+/// The weight of the groups that are not configured, like the default
+/// `cpu.weight` of a cgroup.
+pub const DEFAULT_WEIGHT: NonZeroUsize = NonZeroUsize::new(100).unwrap();
+
+/// The CPU weights of the process groups: while their processes are ready,
+/// every group gets a share of the processor proportional to its weight.
+/// A group with a [`Bandwidth`] is also limited to a quota of processor
+/// time per period.
+///
+/// ## Example
+///
+/// ```rust
+/// use std::num::NonZeroUsize;
+/// use scheduler::{Bandwidth, GroupConfig, DEFAULT_WEIGHT};
+///
+/// let groups = GroupConfig::default()
+///     .weight(1, NonZeroUsize::new(300).unwrap())
+///     .bandwidth(2, "5/20".parse().unwrap());
+///
+/// assert_eq!(groups.weight_of(1).get(), 300);
+/// assert_eq!(groups.weight_of(2), DEFAULT_WEIGHT);
+/// assert_eq!(groups.bandwidth[&2], Bandwidth::new(5, 20));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GroupConfig {
+    /// The weights of the groups, by group number.
+    pub weights: BTreeMap<usize, NonZeroUsize>,
+
+    /// The processor bandwidth of the limited groups, by group number.
+    pub bandwidth: BTreeMap<usize, Bandwidth>,
+}
+
+impl GroupConfig {
+    /// Set the weight of a group.
+    pub fn weight(mut self, group: usize, weight: NonZeroUsize) -> GroupConfig {
+        self.weights.insert(group, weight);
+        self
+    }
+
+    /// Limit the processor time of a group.
+    pub fn bandwidth(mut self, group: usize, bandwidth: Bandwidth) -> GroupConfig {
+        self.bandwidth.insert(group, bandwidth);
+        self
+    }
+
+    /// The weight of a group, [`DEFAULT_WEIGHT`] if it is not configured.
+    pub fn weight_of(&self, group: usize) -> NonZeroUsize {
+        self.weights.get(&group).copied().unwrap_or(DEFAULT_WEIGHT)
+    }
+}
+
+/// A group may use the processor for at most `quota` time units in every
+/// `period`, like the `cpu.max` of a cgroup. The periods start at the
+/// multiples of `period`, when the processes of a group use its quota they
+/// are [throttled](crate::ProcessState::Throttled) until the next one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bandwidth {
+    pub quota: NonZeroUsize,
+    pub period: NonZeroUsize,
+}
+
+impl Bandwidth {
+    /// Create a bandwidth, panics if `quota` or `period` is 0.
+    pub fn new(quota: usize, period: usize) -> Bandwidth {
+        Bandwidth {
+            quota: NonZeroUsize::new(quota).unwrap(),
+            period: NonZeroUsize::new(period).unwrap(),
+        }
+    }
+
+    /// The index of the period that contains the time `now`.
+    pub fn period_of(&self, now: usize) -> usize {
+        now / self.period
+    }
+
+    /// The time until the period that contains `now` ends.
+    pub fn until_next(&self, now: usize) -> NonZeroUsize {
+        NonZeroUsize::new(self.period.get() - now % self.period).unwrap()
+    }
+}
+
+/// Shown as `<quota>/<period>`.
+impl Display for Bandwidth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.quota, self.period)
+    }
+}
+
+/// A group with a [`Bandwidth`] has used its quota or has got it back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThrottleEvent {
+    /// The group has used its quota at `time`, its processes wait for the
+    /// next period.
+    Throttled {
+        group: usize,
+        time: usize,
+        used: usize,
+    },
+
+    /// A new period has started at `time` and the throttled group runs
+    /// again, `used` is the time it has used in the previous period.
+    Released {
+        group: usize,
+        time: usize,
+        used: usize,
+    },
+}
+
+/// Shown as `Group <group> throttled at <time> after using <used>`.
+impl Display for ThrottleEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThrottleEvent::Throttled { group, time, used } => {
+                write!(f, "Group {group} throttled at {time} after using {used}")
+            }
+            ThrottleEvent::Released { group, time, used } => {
+                write!(f, "Group {group} released at {time} after using {used}")
+            }
+        }
+    }
+}
+
+/// Parsed from `<quota>/<period>`.
+impl FromStr for Bandwidth {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Bandwidth, String> {
+        let invalid = || format!("invalid bandwidth `{value}`, expected <quota>/<period>");
+        let (quota, period) = value.split_once('/').ok_or_else(invalid)?;
+        Ok(Bandwidth {
+            quota: quota.trim().parse().map_err(|_| invalid())?,
+            period: period.trim().parse().map_err(|_| invalid())?,
+        })
+    }
+}
@@ -4,16 +4,36 @@
 //! to implement a process scheduler.
 //!
 
+use std::collections::{HashSet, VecDeque};
 use std::num::NonZeroUsize;
-use std::collections::VecDeque;
 
+mod aging;
+mod clock;
+mod groups;
+mod observer;
+mod pcb;
+mod reservation;
 mod scheduler;
+mod sleep_queue;
+mod wait_queues;
 
-use schedulers::RoundRobin;
+use schedulers::{
+    Classes, DeadlineScheduler, Deadlines, FairShare, GroupFairShare, Periods, Priorities,
+    PriorityQueue, RateMonotonic, RoundRobin, RtClasses,
+};
 
+pub use crate::aging::{Aged, Aging};
+pub use crate::clock::Clock;
+pub use crate::groups::{Bandwidth, GroupConfig, ThrottleEvent, DEFAULT_WEIGHT};
+pub use crate::observer::{Event, Observed, Observer};
+pub use crate::pcb::{Pcb, PcbExt};
+pub use crate::reservation::Reservation;
 pub use crate::scheduler::{
-    Pid, Process, ProcessState, Scheduler, SchedulingDecision, StopReason, Syscall, SyscallResult,
+    PanicReason, Pid, Process, ProcessState, RtPriority, SchedClass, Scheduler, SchedulerError,
+    SchedulingDecision, StopReason, Syscall, SyscallResult,
 };
+pub use crate::sleep_queue::SleepQueue;
+pub use crate::wait_queues::WaitQueues;
 
 mod schedulers;
 
@@ -22,22 +42,146 @@ mod schedulers;
 ///
 /// * `timeslice` - the time quanta that a process can run before it is preempted
 /// * `minimum_remaining_timeslice` - when a process makes a system call, the scheduler
-///                                 has to decode whether to schedule it again for the
-///                                 remaining time of its quanta, or to schedule a new
-///                                 process. The scheduler will schedule the process
-///                                 again of the remaining quanta is greater or equal to
-///                                 the `minimum_remaining_timeslice` value.
+///   has to decode whether to schedule it again for the remaining time of its quanta,
+///   or to schedule a new process. The scheduler will schedule the process again of
+///   the remaining quanta is greater or equal to the `minimum_remaining_timeslice` value.
 #[allow(unused_variables)]
 pub fn round_robin(timeslice: NonZeroUsize, minimum_remaining_timeslice: usize) -> impl Scheduler {
+    new_round_robin(timeslice, minimum_remaining_timeslice, false)
+}
+
+/// Returns a round robin scheduler like [`round_robin`], with an init-style policy
+/// for orphans: the children of an exiting process are adopted by the process with
+/// PID 1, and when PID 1 exits before the others, the scheduler keeps running until
+/// they finish instead of returning a [`SchedulingDecision::Panic`].
+pub fn round_robin_adopting_orphans(
+    timeslice: NonZeroUsize,
+    minimum_remaining_timeslice: usize,
+) -> impl Scheduler {
+    new_round_robin(timeslice, minimum_remaining_timeslice, true)
+}
+
+fn new_round_robin(
+    timeslice: NonZeroUsize,
+    minimum_remaining_timeslice: usize,
+    adopt_orphans: bool,
+) -> RoundRobin {
     RoundRobin {
         ready_q: VecDeque::new(),
-        wait_q: VecDeque::new(),
-        sleep_q: VecDeque::new(),
-        timeslice: timeslice,
-        minimum_remaining_timeslice: minimum_remaining_timeslice,
+        wait_q: WaitQueues::default(),
+        sleep_q: SleepQueue::default(),
+        timeslice,
+        minimum_remaining_timeslice,
         init_pid: 0,
-        panic_state: true,
-        sleep_time: 0,
+        panic: None,
+        adopt_orphans,
+        clock: Clock::default(),
         default_timeslice: timeslice,
+        latched_events: HashSet::new(),
     }
 }
+
+/// Returns a structure that implements the `Scheduler` trait with a priority scheduler
+/// policy: the ready process with the largest priority runs first, processes with the
+/// same priority take turns like in a round robin
+///
+/// * `timeslice` - the time quanta that a process can run before it is preempted
+/// * `minimum_remaining_timeslice` - the minimum remaining quanta for a process to go on
+///   running after a system call, like for [`round_robin`]
+/// * `aging` - raises the priority of the processes that wait and detects the ones that
+///   starve, [`Aging::default`] keeps the priorities unchanged
+pub fn priority_queue(
+    timeslice: NonZeroUsize,
+    minimum_remaining_timeslice: usize,
+    aging: Aging,
+) -> impl Scheduler {
+    PriorityQueue::new(
+        Priorities::new(timeslice, aging),
+        minimum_remaining_timeslice,
+    )
+}
+
+/// Returns a structure that implements the `Scheduler` trait with real-time scheduling
+/// classes: the [`SchedClass::Fifo`] and [`SchedClass::RoundRobin`] processes run by
+/// decreasing real-time priority, always before the [`SchedClass::Other`] processes,
+/// that take turns like in a round robin. A FIFO process keeps the processor until it
+/// blocks, yields or a process with a higher priority is ready.
+///
+/// The processes choose their class with a [`Syscall::ForkClass`] or a
+/// [`Syscall::SetClass`], the ones created by a [`Syscall::Fork`] inherit the class
+/// of their parent.
+///
+/// * `timeslice` - the time quanta that a process can run before it is preempted
+/// * `minimum_remaining_timeslice` - the minimum remaining quanta for a process to go on
+///   running after a system call, like for [`round_robin`]
+pub fn rt_classes(timeslice: NonZeroUsize, minimum_remaining_timeslice: usize) -> impl Scheduler {
+    RtClasses::new(Classes::new(timeslice), minimum_remaining_timeslice)
+}
+
+/// Returns a structure that implements the `Scheduler` trait with a group fair-share
+/// policy: the scheduler first picks the group that has used the least processor time
+/// relative to its weight, then the processes of the group take turns like in a round
+/// robin. The first process starts in group 0, a forked process starts in the group of
+/// its parent and [`Syscall::SetGroup`] moves a process to another group.
+///
+/// * `timeslice` - the time quanta that a process can run before it is preempted
+/// * `minimum_remaining_timeslice` - the minimum remaining quanta for a process to go on
+///   running after a system call, like for [`round_robin`]
+/// * `groups` - the CPU weights of the groups
+pub fn fair_share(
+    timeslice: NonZeroUsize,
+    minimum_remaining_timeslice: usize,
+    groups: GroupConfig,
+) -> impl Scheduler {
+    GroupFairShare::new(
+        FairShare::new(timeslice, groups),
+        minimum_remaining_timeslice,
+    )
+}
+
+/// Returns a structure that implements the `Scheduler` trait with an earliest deadline
+/// first policy: the ready process created by a [`Syscall::ForkReserved`] whose deadline
+/// comes first runs, always before the processes without a [`Reservation`], that take
+/// turns like in a round robin. A process gets a new deadline, `deadline` time units away,
+/// when it becomes ready after its previous one has passed. The processes use as much
+/// processor time as they need, so one that runs for longer than it has declared can
+/// make the others miss their deadlines.
+///
+/// * `timeslice` - the time quanta that a process can run before it is preempted
+/// * `minimum_remaining_timeslice` - the minimum remaining quanta for a process to go on
+///   running after a system call, like for [`round_robin`]
+pub fn edf(timeslice: NonZeroUsize, minimum_remaining_timeslice: usize) -> impl Scheduler {
+    DeadlineScheduler::new(Deadlines::edf(timeslice), minimum_remaining_timeslice)
+}
+
+/// Returns a structure that implements the `Scheduler` trait with processor reservations,
+/// like `SCHED_DEADLINE`: the processes are scheduled by deadline like for [`edf`], and
+/// every [`Reservation`] is served by a constant bandwidth server. A process that has used
+/// the runtime of its reservation gets it back with the deadline of its next period, so
+/// it runs after the processes that have not used theirs.
+///
+/// A [`Syscall::ForkReserved`] is denied with [`SyscallResult::AdmissionDenied`] if the
+/// utilisations of the reservations would add up to more than the whole processor.
+///
+/// * `timeslice` - the time quanta that a process can run before it is preempted
+/// * `minimum_remaining_timeslice` - the minimum remaining quanta for a process to go on
+///   running after a system call, like for [`round_robin`]
+pub fn reservations(timeslice: NonZeroUsize, minimum_remaining_timeslice: usize) -> impl Scheduler {
+    DeadlineScheduler::new(Deadlines::servers(timeslice), minimum_remaining_timeslice)
+}
+
+/// Returns a structure that implements the `Scheduler` trait with a rate monotonic policy
+/// for periodic processes: the ready process created by a [`Syscall::ForkReserved`] with
+/// the shortest period runs, always before the processes without a [`Reservation`], that
+/// take turns like in a round robin. Only the period of the reservation is used, the
+/// processes are neither limited to their runtime nor admitted by utilisation.
+///
+/// * `timeslice` - the time quanta that a process can run before it is preempted
+/// * `minimum_remaining_timeslice` - the minimum remaining quanta for a process to go on
+///   running after a system call, like for [`round_robin`]
+pub fn rate_monotonic(
+    timeslice: NonZeroUsize,
+    minimum_remaining_timeslice: usize,
+) -> impl Scheduler {
+    RateMonotonic::new(Periods::new(timeslice), minimum_remaining_timeslice)
+}
@@ -0,0 +1,239 @@
+//! Hooks that observe any [`Scheduler`] without changing its policy.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, Mutex};
+
+use crate::groups::ThrottleEvent;
+use crate::scheduler::{
+    Pid, Process, ProcessState, Scheduler, SchedulingDecision, StopReason, SyscallResult,
+};
+
+/// Receives the actions of a scheduler wrapped by [`Observed`].
+///
+/// Every method does nothing by default, so an observer only implements
+/// the hooks it needs.
+///
+/// A process is in a queue while it is [`ProcessState::Ready`] or
+/// [`ProcessState::Waiting`], the state is the queue it is in. A running
+/// process is not in any queue.
+///
+/// The queue hooks are not called by the queues themselves: [`Observed`]
+/// compares the states listed before and after every call, so
+///
+/// * a process that leaves a queue and comes back to one with the same
+///   state is not reported, like a ready process repositioned by a
+///   [`Syscall::SetPriority`](crate::Syscall::SetPriority), or one that
+///   runs and yields between two calls and is listed as ready by both;
+/// * every [`Scheduler::next`] and [`Scheduler::stop`] costs a
+///   [`Scheduler::list`] call, two for every system call.
+pub trait Observer: Send {
+    /// The scheduler has returned a decision from [`Scheduler::next`].
+    #[allow(unused_variables)]
+    fn on_decision(&mut self, decision: SchedulingDecision) {}
+
+    /// The scheduler has returned `result` from [`Scheduler::stop`].
+    #[allow(unused_variables)]
+    fn on_stop(&mut self, reason: StopReason, result: SyscallResult) {}
+
+    /// The state of a process has changed, `from` is `None` for a new
+    /// process and `to` is `None` for a process that has exited.
+    #[allow(unused_variables)]
+    fn on_state_change(&mut self, pid: Pid, from: Option<ProcessState>, to: Option<ProcessState>) {}
+
+    /// A process has been added to the queue of `state`.
+    #[allow(unused_variables)]
+    fn on_enqueue(&mut self, pid: Pid, state: ProcessState) {}
+
+    /// A process has been removed from the queue of `state`.
+    #[allow(unused_variables)]
+    fn on_dequeue(&mut self, pid: Pid, state: ProcessState) {}
+}
+
+/// Lets the owner of the observer read it while the scheduler uses it.
+impl<O: Observer> Observer for Arc<Mutex<O>> {
+    fn on_decision(&mut self, decision: SchedulingDecision) {
+        self.lock().unwrap().on_decision(decision)
+    }
+
+    fn on_stop(&mut self, reason: StopReason, result: SyscallResult) {
+        self.lock().unwrap().on_stop(reason, result)
+    }
+
+    fn on_state_change(&mut self, pid: Pid, from: Option<ProcessState>, to: Option<ProcessState>) {
+        self.lock().unwrap().on_state_change(pid, from, to)
+    }
+
+    fn on_enqueue(&mut self, pid: Pid, state: ProcessState) {
+        self.lock().unwrap().on_enqueue(pid, state)
+    }
+
+    fn on_dequeue(&mut self, pid: Pid, state: ProcessState) {
+        self.lock().unwrap().on_dequeue(pid, state)
+    }
+}
+
+/// A hook call, recorded by the [`Observer`] implementation of `Vec<Event>`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Event {
+    Decision(SchedulingDecision),
+    Stop {
+        reason: StopReason,
+        result: SyscallResult,
+    },
+    StateChange {
+        pid: Pid,
+        from: Option<ProcessState>,
+        to: Option<ProcessState>,
+    },
+    Enqueue {
+        pid: Pid,
+        state: ProcessState,
+    },
+    Dequeue {
+        pid: Pid,
+        state: ProcessState,
+    },
+}
+
+impl Observer for Vec<Event> {
+    fn on_decision(&mut self, decision: SchedulingDecision) {
+        self.push(Event::Decision(decision));
+    }
+
+    fn on_stop(&mut self, reason: StopReason, result: SyscallResult) {
+        self.push(Event::Stop { reason, result });
+    }
+
+    fn on_state_change(&mut self, pid: Pid, from: Option<ProcessState>, to: Option<ProcessState>) {
+        self.push(Event::StateChange { pid, from, to });
+    }
+
+    fn on_enqueue(&mut self, pid: Pid, state: ProcessState) {
+        self.push(Event::Enqueue { pid, state });
+    }
+
+    fn on_dequeue(&mut self, pid: Pid, state: ProcessState) {
+        self.push(Event::Dequeue { pid, state });
+    }
+}
+
+/// A scheduler that calls its observers after every [`Scheduler::next`]
+/// and [`Scheduler::stop`].
+///
+/// The state changes are found by comparing [`Scheduler::list`] before and
+/// after the call, so they are reported in the order of the PIDs, after the
+/// decision or the stop.
+///
+/// ## Example
+///
+/// ```rust
+/// use std::num::NonZeroUsize;
+/// use std::sync::{Arc, Mutex};
+/// use scheduler::{Event, Observed, Scheduler, StopReason, Syscall};
+///
+/// let events = Arc::new(Mutex::new(Vec::<Event>::new()));
+/// let mut scheduler = Observed::new(scheduler::round_robin(NonZeroUsize::new(3).unwrap(), 1));
+/// scheduler.attach(events.clone());
+/// scheduler.stop(StopReason::syscall(Syscall::Fork(0)));
+/// assert_eq!(events.lock().unwrap().len(), 3);
+/// ```
+pub struct Observed<S: Scheduler> {
+    scheduler: S,
+    observers: Vec<Box<dyn Observer>>,
+    states: BTreeMap<Pid, ProcessState>,
+}
+
+impl<S: Scheduler> Observed<S> {
+    /// Wrap a scheduler, without observers.
+    pub fn new(scheduler: S) -> Observed<S> {
+        Observed {
+            scheduler,
+            observers: vec![],
+            states: BTreeMap::new(),
+        }
+    }
+
+    /// Add an observer, it sees the changes made after it was attached.
+    pub fn attach<O: Observer + 'static>(&mut self, observer: O) {
+        self.states = self.snapshot();
+        self.observers.push(Box::new(observer));
+    }
+
+    /// The wrapped scheduler.
+    pub fn inner(&self) -> &S {
+        &self.scheduler
+    }
+
+    fn snapshot(&mut self) -> BTreeMap<Pid, ProcessState> {
+        self.scheduler
+            .list()
+            .iter()
+            .map(|process| (process.pid(), process.state()))
+            .collect()
+    }
+
+    /// Report the state changes since the last call.
+    fn changes(&mut self) {
+        if self.observers.is_empty() {
+            return;
+        }
+        let states = self.snapshot();
+        let pids = self.states.keys().chain(states.keys()).copied();
+        for pid in pids.collect::<BTreeSet<Pid>>() {
+            let (from, to) = (self.states.get(&pid).copied(), states.get(&pid).copied());
+            if from == to {
+                continue;
+            }
+            for observer in self.observers.iter_mut() {
+                if let Some(from) = from.filter(queued) {
+                    observer.on_dequeue(pid, from);
+                }
+                observer.on_state_change(pid, from, to);
+                if let Some(to) = to.filter(queued) {
+                    observer.on_enqueue(pid, to);
+                }
+            }
+        }
+        self.states = states;
+    }
+}
+
+fn queued(state: &ProcessState) -> bool {
+    *state != ProcessState::Running
+}
+
+impl<S: Scheduler> Scheduler for Observed<S> {
+    fn next(&mut self) -> SchedulingDecision {
+        let decision = self.scheduler.next();
+        for observer in self.observers.iter_mut() {
+            observer.on_decision(decision);
+        }
+        self.changes();
+        decision
+    }
+
+    fn stop(&mut self, reason: StopReason) -> SyscallResult {
+        let result = self.scheduler.stop(reason);
+        for observer in self.observers.iter_mut() {
+            observer.on_stop(reason, result);
+        }
+        self.changes();
+        result
+    }
+
+    fn list(&mut self) -> Vec<&dyn Process> {
+        self.scheduler.list()
+    }
+
+    fn context_switch(&mut self, time: usize) {
+        self.scheduler.context_switch(time)
+    }
+
+    fn io_completion(&mut self, pid: Pid, wakeup: usize) {
+        self.scheduler.io_completion(pid, wakeup)
+    }
+
+    fn throttle_events(&mut self) -> Vec<ThrottleEvent> {
+        self.scheduler.throttle_events()
+    }
+}
@@ -0,0 +1,152 @@
+//! A process control block that every scheduler can store.
+
+use std::fmt::{self, Display};
+
+use crate::scheduler::{Pid, Process, ProcessState};
+
+/// The data that a policy adds to its [`Pcb`].
+pub trait PcbExt: Send {
+    /// The details shown by [`Process::extra`].
+    fn extra(&self) -> String {
+        String::new()
+    }
+}
+
+/// For policies that only need the standard fields.
+impl PcbExt for () {}
+
+/// For copies of processes, that keep only their details.
+impl PcbExt for String {
+    fn extra(&self) -> String {
+        self.clone()
+    }
+}
+
+/// A process control block, with the fields that every policy needs
+/// and an extension for the policy specific ones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pcb<Ext: PcbExt = ()> {
+    /// The PID of the process.
+    pub pid: Pid,
+
+    /// The process that has forked this process.
+    pub parent: Option<Pid>,
+
+    /// The process state.
+    pub state: ProcessState,
+
+    /// The process timings (total time, system call time, running time).
+    pub timings: (usize, usize, usize),
+
+    /// The process priority.
+    pub priority: i8,
+
+    /// The [`Clock`](crate::Clock) time when the process wakes up, while it sleeps.
+    pub wakeup: usize,
+
+    /// The time the process has been ready since it was last scheduled,
+    /// policies reset it when they schedule the process.
+    pub waited: usize,
+
+    /// The process group, the children start in the group of their parent.
+    pub group: usize,
+
+    /// The policy specific data.
+    pub ext: Ext,
+}
+
+impl<Ext: PcbExt> Pcb<Ext> {
+    /// Create a ready process, that has not used any time yet.
+    pub fn new(pid: Pid, parent: Option<Pid>, priority: i8, ext: Ext) -> Pcb<Ext> {
+        Pcb {
+            pid,
+            parent,
+            state: ProcessState::Ready,
+            timings: (0, 0, 0),
+            priority,
+            wakeup: 0,
+            waited: 0,
+            group: 0,
+            ext,
+        }
+    }
+
+    /// Add the time that has passed since the process was created, whatever its
+    /// state, and to the time it has waited if it is ready.
+    pub fn elapse(&mut self, time: usize) {
+        self.timings.0 += time;
+        if self.state == ProcessState::Ready {
+            self.waited += time;
+        }
+    }
+
+    /// Add the time the process has spent running its own instructions.
+    pub fn execute(&mut self, time: usize) {
+        self.timings.2 += time;
+    }
+
+    /// Count a system call sent by the process.
+    pub fn syscall(&mut self) {
+        self.timings.1 += 1;
+    }
+}
+
+impl Pcb<String> {
+    /// Copy the data that any [`Process`] exposes, the parent, the wakeup
+    /// time and the time waited are not known.
+    pub fn snapshot(process: &dyn Process) -> Pcb<String> {
+        Pcb {
+            pid: process.pid(),
+            parent: None,
+            state: process.state(),
+            timings: process.timings(),
+            priority: process.priority(),
+            wakeup: 0,
+            waited: 0,
+            group: process.group(),
+            ext: process.extra(),
+        }
+    }
+}
+
+impl<Ext: PcbExt> Process for Pcb<Ext> {
+    fn pid(&self) -> Pid {
+        self.pid
+    }
+
+    fn state(&self) -> ProcessState {
+        self.state
+    }
+
+    fn timings(&self) -> (usize, usize, usize) {
+        self.timings
+    }
+
+    fn priority(&self) -> i8 {
+        self.priority
+    }
+
+    fn extra(&self) -> String {
+        self.ext.extra()
+    }
+
+    fn group(&self) -> usize {
+        self.group
+    }
+}
+
+impl<Ext: PcbExt> Display for Pcb<Ext> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}\t{}\t\t{}\t{}\t{}\t{}\t{}",
+            self.pid,
+            self.state,
+            self.priority,
+            self.timings.0,
+            self.timings.1,
+            self.timings.2,
+            self.ext.extra()
+        )
+    }
+}
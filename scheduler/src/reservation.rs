@@ -0,0 +1,102 @@
+//! Processor reservations, for the policies that schedule the processes by
+//! deadline.
+
+use std::fmt::{self, Display};
+use std::num::NonZeroUsize;
+
+use crate::scheduler::SchedulerError;
+
+/// A process reserves `runtime` time units of processor time in every
+/// `period`, to be served within `deadline` time units from the start of the
+/// period, like the `sched_runtime`, `sched_deadline` and `sched_period` of a
+/// `SCHED_DEADLINE` thread.
+///
+/// ## Example
+///
+/// ```rust
+/// use scheduler::Reservation;
+///
+/// let reservation = Reservation::new(2, 5, 10);
+/// assert_eq!(reservation.utilisation(), 0.2);
+/// assert!(Reservation::fits([reservation; 5]));
+/// assert!(!Reservation::fits([reservation; 6]));
+/// assert!(Reservation::try_new(3, 2, 10).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Reservation {
+    pub runtime: NonZeroUsize,
+    pub deadline: NonZeroUsize,
+    pub period: NonZeroUsize,
+}
+
+impl Reservation {
+    /// Create a reservation.
+    ///
+    /// Panics if the values are not `0 < runtime <= deadline <= period`, use
+    /// [`Reservation::try_new`] for values that are not checked.
+    pub fn new(runtime: usize, deadline: usize, period: usize) -> Reservation {
+        Reservation::try_new(runtime, deadline, period).unwrap()
+    }
+
+    /// Create a reservation, or return [`SchedulerError::InvalidReservation`]
+    /// if the values are not `0 < runtime <= deadline <= period`.
+    pub fn try_new(
+        runtime: usize,
+        deadline: usize,
+        period: usize,
+    ) -> Result<Reservation, SchedulerError> {
+        match NonZeroUsize::new(runtime) {
+            Some(runtime) if runtime.get() <= deadline && deadline <= period => Ok(Reservation {
+                runtime,
+                deadline: NonZeroUsize::new(deadline).unwrap(),
+                period: NonZeroUsize::new(period).unwrap(),
+            }),
+            _ => Err(SchedulerError::InvalidReservation {
+                runtime,
+                deadline,
+                period,
+            }),
+        }
+    }
+
+    /// The share of the processor that is reserved, `runtime / period`.
+    pub fn utilisation(&self) -> f64 {
+        self.runtime.get() as f64 / self.period.get() as f64
+    }
+
+    /// Whether the processor can serve all the reservations, their
+    /// utilisations add up to at most 1. The sum is exact, without the
+    /// rounding of [`Reservation::utilisation`].
+    pub fn fits(reservations: impl IntoIterator<Item = Reservation>) -> bool {
+        // the sum is the fraction `numerator / denominator`
+        let (mut numerator, mut denominator) = (0u128, 1u128);
+        for reservation in reservations {
+            let runtime = reservation.runtime.get() as u128;
+            let period = reservation.period.get() as u128;
+            numerator = numerator * period + runtime * denominator;
+            denominator *= period;
+            let divisor = gcd(numerator, denominator);
+            numerator /= divisor;
+            denominator /= divisor;
+            if numerator > denominator {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Shown as `<runtime>/<deadline>/<period>`.
+impl Display for Reservation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}/{}", self.runtime, self.deadline, self.period)
+    }
+}
+
+fn gcd(a: u128, b: u128) -> u128 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
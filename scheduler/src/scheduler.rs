@@ -2,6 +2,9 @@ use std::fmt::{self, Display};
 use std::num::NonZeroUsize;
 use std::ops::Add;
 
+use crate::groups::ThrottleEvent;
+use crate::reservation::Reservation;
+
 /// The PID of a process
 ///
 /// The PID cannot be 0, PIDs start from 1.
@@ -10,8 +13,18 @@ use std::ops::Add;
 pub struct Pid(NonZeroUsize);
 
 impl Pid {
+    /// Create a PID.
+    ///
+    /// Panics if `pid` is 0, use [`Pid::try_new`] for values that are not checked.
     pub fn new(pid: usize) -> Pid {
-        Pid(NonZeroUsize::new(pid).unwrap())
+        Pid::try_new(pid).unwrap()
+    }
+
+    /// Create a PID, or return [`SchedulerError::InvalidPid`] if `pid` is 0.
+    pub fn try_new(pid: usize) -> Result<Pid, SchedulerError> {
+        NonZeroUsize::new(pid)
+            .map(Pid)
+            .ok_or(SchedulerError::InvalidPid(pid))
     }
 }
 
@@ -56,8 +69,11 @@ pub enum SchedulingDecision {
     /// that all the processes will wait indefinitely.
     Deadlock,
 
-    /// The process with PID 1 has stopped.
-    Panic,
+    /// The OS cannot continue, usually because the process with PID 1 has stopped.
+    Panic(
+        /// Why the OS cannot continue.
+        PanicReason,
+    ),
 
     /// There are no more processes to schedule.
     Done,
@@ -75,8 +91,8 @@ impl Display for SchedulingDecision {
             SchedulingDecision::Deadlock => {
                 write!(f, "Deadlock, unable to schedule anymore processes")
             }
-            SchedulingDecision::Panic => {
-                write!(f, "Panic, process 1 has stopped")
+            SchedulingDecision::Panic(reason) => {
+                write!(f, "Panic, {reason}")
             }
             SchedulingDecision::Done => {
                 write!(f, "Done, no more processes")
@@ -85,6 +101,40 @@ impl Display for SchedulingDecision {
     }
 }
 
+/// The reason of a [`SchedulingDecision::Panic`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PanicReason {
+    /// No process has been created, so there is no process with PID 1.
+    NoInit,
+
+    /// The process with PID 1 has exited while other processes were alive.
+    ///
+    /// Schedulers that let orphans be adopted keep running instead.
+    InitExited {
+        /// The number of processes that were still alive.
+        alive: usize,
+    },
+
+    /// The scheduler has broken its contract, the [`SchedulerError`]
+    /// is reported by the OS.
+    ContractViolation,
+}
+
+impl Display for PanicReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PanicReason::NoInit => write!(f, "there is no process 1"),
+            PanicReason::InitExited { alive: 1 } => {
+                write!(f, "process 1 has exited while 1 process was alive")
+            }
+            PanicReason::InitExited { alive } => {
+                write!(f, "process 1 has exited while {alive} processes were alive")
+            }
+            PanicReason::ContractViolation => write!(f, "the scheduler has broken its contract"),
+        }
+    }
+}
+
 /// A system call that processes make towards the scheduler.
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum Syscall {
@@ -94,6 +144,32 @@ pub enum Syscall {
         i8,
     ),
 
+    /// Create a new process in a scheduling class and return its PID.
+    ///
+    /// Schedulers without classes handle it like a [`Syscall::Fork`].
+    ForkClass {
+        /// The process's priority, used by the [`SchedClass::Other`] class.
+        priority: i8,
+
+        /// The scheduling class of the new process. The processes created
+        /// by a [`Syscall::Fork`] inherit the class of their parent.
+        class: SchedClass,
+    },
+
+    /// Create a new process with a processor reservation and return its PID,
+    /// or [`SyscallResult::AdmissionDenied`] if the scheduler cannot serve
+    /// the reservation together with the ones it has already admitted.
+    ///
+    /// Schedulers without reservations handle it like a [`Syscall::Fork`].
+    ForkReserved {
+        /// The process's priority, used while it has no deadline to meet.
+        priority: i8,
+
+        /// The processor time the new process needs and how soon. The
+        /// processes created by a [`Syscall::Fork`] have no reservation.
+        reservation: Reservation,
+    },
+
     /// Ask the scheduler to suspend for an amount of time
     Sleep(
         /// The amount of time that the process should sleep. The process
@@ -102,6 +178,20 @@ pub enum Syscall {
         usize,
     ),
 
+    /// Ask a device to serve a request and block until it completes.
+    ///
+    /// The process is placed in the [`ProcessState::Waiting`] state, without
+    /// an event, until the request completes, just like for [`Syscall::Sleep`].
+    /// The processor serves the requests for a device in order and reports
+    /// when this one completes with [`Scheduler::io_completion`].
+    Io {
+        /// The device number.
+        device: usize,
+
+        /// The amount of time the device needs to serve the request.
+        duration: usize,
+    },
+
     /// Wait for an event
     Wait(
         /// The event number. The process will be placed in the [`ProcessState::Waiting`]
@@ -117,6 +207,65 @@ pub enum Syscall {
         usize,
     ),
 
+    /// Signal only the process that has waited the longest for an event.
+    SignalOne(
+        /// The event number. The first process that started waiting for this
+        /// event will be woken up, the others keep waiting.
+        usize,
+    ),
+
+    /// Signal all processes that wait for an event and latch the event
+    /// if no process is waiting for it.
+    SignalLatched(
+        /// The event number. If no process is waiting for this event, the
+        /// event is remembered and the next [`Syscall::Wait`] for it returns
+        /// immediately, consuming the event.
+        usize,
+    ),
+
+    /// Change the priority of a process.
+    ///
+    /// A process can change only its own priority or the priority of
+    /// the processes it has forked. Schedulers that take priorities into
+    /// account reposition the process right away.
+    SetPriority(
+        /// The PID of the process whose priority is changed.
+        Pid,
+        /// The new priority.
+        i8,
+    ),
+
+    /// Change the scheduling class of a process.
+    ///
+    /// A process can change only its own class or the class of the processes
+    /// it has forked. Schedulers without classes ignore the value.
+    SetClass(
+        /// The PID of the process whose class is changed.
+        Pid,
+        /// The new class.
+        SchedClass,
+    ),
+
+    /// Move a process to another process group.
+    ///
+    /// A process can move only itself or the processes it has forked, the
+    /// processes created by a [`Syscall::Fork`] start in the group of their
+    /// parent. Schedulers without groups ignore the value.
+    SetGroup(
+        /// The PID of the process that is moved.
+        Pid,
+        /// The new group, the first process starts in group 0.
+        usize,
+    ),
+
+    /// Give up the rest of the timeslice.
+    ///
+    /// The process is placed at the tail of the ready queue, regardless of
+    /// the time it has left from its quanta. It is accounted like any other
+    /// system call: one time unit of system call time, the rest of the used
+    /// quanta as execution time.
+    Yield,
+
     /// Ask the scheduler to finish the process.
     ///
     /// The process will never be scheduled again and will be deleted
@@ -124,6 +273,18 @@ pub enum Syscall {
     Exit,
 }
 
+impl Syscall {
+    /// The amount of time that a [`Syscall::Sleep`] blocks the process, or
+    /// that a [`Syscall::Io`] does if it does not wait for other requests.
+    pub fn blocking_time(&self) -> Option<usize> {
+        match *self {
+            Syscall::Sleep(time) => Some(time),
+            Syscall::Io { duration, .. } => Some(duration),
+            _ => None,
+        }
+    }
+}
+
 /*
 ///
 /// If all the processes are in the sleep state, the scheduler will return
@@ -143,10 +304,163 @@ pub enum SyscallResult {
     /// This is the value returned by most system calls.
     Success,
 
+    /// Returned after a [`Syscall::Signal`], [`Syscall::SignalOne`] or
+    /// [`Syscall::SignalLatched`] system call.
+    Woken(
+        /// The number of processes that were woken up.
+        usize,
+    ),
+
     /// The system call was issues while no process was scheduled.
     NoRunningProcess,
+
+    /// The PID given to the system call does not belong to any process.
+    InvalidPid,
+
+    /// The process is not allowed to perform the system call on the
+    /// given process.
+    PermissionDenied,
+
+    /// The system call cannot be performed with the given arguments,
+    /// for instance the process has more remaining time than its timeslice.
+    InvalidArgument,
+
+    /// Returned after a [`Syscall::ForkReserved`] system call, when the
+    /// utilisation of the reservations would exceed the whole processor.
+    /// The process is not created.
+    AdmissionDenied,
 }
 
+/// A real-time priority, from 1, the lowest, to 99, the highest.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RtPriority(u8);
+
+impl RtPriority {
+    /// Create a real-time priority.
+    ///
+    /// Panics if `priority` is not between 1 and 99, use [`RtPriority::try_new`]
+    /// for values that are not checked.
+    pub fn new(priority: u8) -> RtPriority {
+        RtPriority::try_new(priority).unwrap()
+    }
+
+    /// Create a real-time priority, or return [`SchedulerError::InvalidRtPriority`]
+    /// if `priority` is not between 1 and 99.
+    pub fn try_new(priority: u8) -> Result<RtPriority, SchedulerError> {
+        if (1..=99).contains(&priority) {
+            Ok(RtPriority(priority))
+        } else {
+            Err(SchedulerError::InvalidRtPriority(priority))
+        }
+    }
+
+    /// The priority, from 1 to 99.
+    pub fn get(&self) -> u8 {
+        self.0
+    }
+}
+
+impl Display for RtPriority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// The scheduling class of a process, like the Linux scheduling policies.
+///
+/// A ready real-time process always runs before the processes of the
+/// [`SchedClass::Other`] class, and before the real-time processes with
+/// a lower priority.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub enum SchedClass {
+    /// `SCHED_FIFO`: the process runs until it blocks, yields or a process
+    /// with a higher priority is ready, it is not preempted when its
+    /// timeslice expires.
+    Fifo(RtPriority),
+
+    /// `SCHED_RR`: like [`SchedClass::Fifo`], but the processes with the
+    /// same priority take turns when their timeslice expires.
+    RoundRobin(RtPriority),
+
+    /// `SCHED_OTHER`: the normal processes, that run only when no real-time
+    /// process is ready.
+    #[default]
+    Other,
+}
+
+impl SchedClass {
+    /// The real-time priority, if the class is a real-time one.
+    pub fn rt_priority(&self) -> Option<RtPriority> {
+        match self {
+            SchedClass::Fifo(priority) | SchedClass::RoundRobin(priority) => Some(*priority),
+            SchedClass::Other => None,
+        }
+    }
+}
+
+impl Display for SchedClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchedClass::Fifo(priority) => write!(f, "FIFO {priority}"),
+            SchedClass::RoundRobin(priority) => write!(f, "RR {priority}"),
+            SchedClass::Other => write!(f, "OTHER"),
+        }
+    }
+}
+
+/// An error caused by using the scheduler types outside of their contract.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SchedulerError {
+    /// PIDs start from 1, the value cannot be a PID.
+    InvalidPid(usize),
+
+    /// Real-time priorities are between 1 and 99.
+    InvalidRtPriority(u8),
+
+    /// A [`Reservation`] needs `0 < runtime <= deadline <= period`.
+    InvalidReservation {
+        runtime: usize,
+        deadline: usize,
+        period: usize,
+    },
+
+    /// The scheduler has answered with a result that does not fit the
+    /// reason the process stopped, like a [`Syscall::Fork`] that does not
+    /// return a PID or a first process that does not receive PID 1.
+    UnexpectedResult {
+        /// The reason the process has stopped.
+        reason: StopReason,
+
+        /// The result returned by [`Scheduler::stop`].
+        result: SyscallResult,
+    },
+}
+
+impl Display for SchedulerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchedulerError::InvalidPid(pid) => write!(f, "{pid} is not a valid PID"),
+            SchedulerError::InvalidRtPriority(priority) => {
+                write!(f, "{priority} is not a real-time priority")
+            }
+            SchedulerError::InvalidReservation {
+                runtime,
+                deadline,
+                period,
+            } => write!(
+                f,
+                "{runtime}/{deadline}/{period} is not a reservation, \
+                 it needs 0 < runtime <= deadline <= period"
+            ),
+            SchedulerError::UnexpectedResult { reason, result } => {
+                write!(f, "unexpected result {result:?} for {reason}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SchedulerError {}
+
 /// The reason that a process has stopped and the OS
 /// has called the scheduler.
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -208,6 +522,55 @@ pub trait Scheduler: Send {
 
     /// Returns the list of processes.
     fn list(&mut self) -> Vec<&dyn Process>;
+
+    /// The scheduler is informed that the processor has spent `time` units
+    /// switching to the process of the last [`SchedulingDecision::Run`].
+    ///
+    /// The time passes for all the processes, like the time of a
+    /// [`SchedulingDecision::Sleep`], without being charged to any of them.
+    /// The default implementation ignores it.
+    fn context_switch(&mut self, _time: usize) {}
+
+    /// The scheduler is informed that the device request of the process,
+    /// that has just stopped with a [`Syscall::Io`], completes at the
+    /// absolute time `wakeup`, after the requests queued before it.
+    ///
+    /// The process should stay blocked until then. The default implementation
+    /// ignores it and the process wakes up after the duration of the request.
+    fn io_completion(&mut self, _pid: Pid, _wakeup: usize) {}
+
+    /// Take the [`ThrottleEvent`]s of the process groups that have happened
+    /// since the last call. The default implementation has none.
+    fn throttle_events(&mut self) -> Vec<ThrottleEvent> {
+        vec![]
+    }
+}
+
+/// Allows choosing the scheduler at runtime, as a `Box<dyn Scheduler>`.
+impl<S: Scheduler + ?Sized> Scheduler for Box<S> {
+    fn next(&mut self) -> SchedulingDecision {
+        (**self).next()
+    }
+
+    fn stop(&mut self, reason: StopReason) -> SyscallResult {
+        (**self).stop(reason)
+    }
+
+    fn list(&mut self) -> Vec<&dyn Process> {
+        (**self).list()
+    }
+
+    fn context_switch(&mut self, time: usize) {
+        (**self).context_switch(time)
+    }
+
+    fn io_completion(&mut self, pid: Pid, wakeup: usize) {
+        (**self).io_completion(pid, wakeup)
+    }
+
+    fn throttle_events(&mut self) -> Vec<ThrottleEvent> {
+        (**self).throttle_events()
+    }
 }
 
 /// The state of a process.
@@ -226,6 +589,10 @@ pub enum ProcessState {
         /// If the event is [`None`], the process is sleeping.
         event: Option<usize>,
     },
+
+    /// The process is ready, but its group has used its processor quota
+    /// and it waits for the next period.
+    Throttled,
 }
 
 impl Display for ProcessState {
@@ -240,6 +607,7 @@ impl Display for ProcessState {
                     write!(f, "SLEEP")
                 }
             }
+            ProcessState::Throttled => write!(f, "THROTTLED"),
         }
     }
 }
@@ -263,4 +631,9 @@ pub trait Process: Send {
 
     /// Returns details information
     fn extra(&self) -> String;
+
+    /// Returns the process group, for the schedulers that have groups
+    fn group(&self) -> usize {
+        0
+    }
 }
@@ -0,0 +1,199 @@
+use std::collections::VecDeque;
+use std::iter;
+use std::num::NonZeroUsize;
+
+use crate::pcb::{Pcb, PcbExt};
+use crate::reservation::Reservation;
+use crate::scheduler::{Pid, Syscall};
+use crate::schedulers::dispatcher::{Dispatcher, Policy, Requeue};
+
+/// Earliest deadline first scheduler: the ready process with a
+/// [`Reservation`] whose deadline comes first runs, before the processes
+/// without a reservation, that take turns like in a round robin. With
+/// servers, every process is limited to the processor time it has reserved.
+pub type DeadlineScheduler = Dispatcher<Deadlines>;
+
+/// The reservation of a process and its current deadline.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Server {
+    /// The reservation declared by the [`Syscall::ForkReserved`] that has
+    /// created the process.
+    pub reservation: Option<Reservation>,
+
+    /// The processor time the process can still use before its deadline,
+    /// only consumed by the servers.
+    pub budget: usize,
+
+    /// The time by which the process expects to have used its budget.
+    pub deadline: usize,
+}
+
+/// The ready processes of a [`DeadlineScheduler`].
+pub struct Deadlines {
+    /// The ready processes with a reservation, by deadline.
+    pub reserved: VecDeque<Pcb<Server>>,
+    /// The ready processes without a reservation, in the order they became
+    /// ready.
+    pub others: VecDeque<Pcb<Server>>,
+    pub timeslice: NonZeroUsize,
+    /// Whether the reservations are enforced by a constant bandwidth server
+    /// and admitted only if they fit the processor.
+    pub servers: bool,
+    /// The time of the last release.
+    pub now: usize,
+}
+
+impl Deadlines {
+    /// Plain earliest deadline first, the processes use as much processor
+    /// time as they need and every fork is admitted.
+    pub fn edf(timeslice: NonZeroUsize) -> Deadlines {
+        Deadlines {
+            reserved: VecDeque::new(),
+            others: VecDeque::new(),
+            timeslice,
+            servers: false,
+            now: 0,
+        }
+    }
+
+    /// Earliest deadline first with a constant bandwidth server for every
+    /// reservation and admission control.
+    pub fn servers(timeslice: NonZeroUsize) -> Deadlines {
+        Deadlines {
+            servers: true,
+            ..Deadlines::edf(timeslice)
+        }
+    }
+
+    /// A process that becomes ready starts a new job if its deadline has
+    /// passed. A server also starts a new one if the budget it has left
+    /// would use more than its reserved bandwidth until the deadline.
+    fn wake(&self, server: &mut Server) {
+        let Some(reservation) = server.reservation else {
+            return;
+        };
+        let expired = server.deadline <= self.now;
+        let overflow = !expired
+            && self.servers
+            && server.budget * reservation.deadline.get()
+                > (server.deadline - self.now) * reservation.runtime.get();
+        if expired || overflow {
+            server.deadline = self.now + reservation.deadline.get();
+            server.budget = reservation.runtime.get();
+        }
+    }
+}
+
+impl Policy for Deadlines {
+    type Ext = Server;
+
+    // the reservation is not inherited, like for `SCHED_DEADLINE`
+    fn fork(&mut self, _parent: Option<&Pcb<Server>>, syscall: Syscall) -> Server {
+        match syscall {
+            Syscall::ForkReserved { reservation, .. } => Server {
+                reservation: Some(reservation),
+                ..Server::default()
+            },
+            _ => Server::default(),
+        }
+    }
+
+    fn admits<'a>(&self, syscall: Syscall, others: impl Iterator<Item = &'a Pcb<Server>>) -> bool {
+        let Syscall::ForkReserved { reservation, .. } = syscall else {
+            return true;
+        };
+        !self.servers
+            || Reservation::fits(
+                self.iter()
+                    .filter_map(|process| process.ext.reservation)
+                    .chain(others.filter_map(|process| process.ext.reservation))
+                    .chain(iter::once(reservation)),
+            )
+    }
+
+    fn push(&mut self, mut process: Pcb<Server>, requeue: Requeue) {
+        if process.ext.reservation.is_none() {
+            self.others.push_back(process);
+            return;
+        }
+        if matches!(requeue, Requeue::Ready | Requeue::Yielded) {
+            self.wake(&mut process.ext);
+        }
+        // a preempted process goes on before the others with its deadline
+        let deadline = process.ext.deadline;
+        let index = self.reserved.partition_point(|other| match requeue {
+            Requeue::Preempted => other.ext.deadline < deadline,
+            _ => other.ext.deadline <= deadline,
+        });
+        self.reserved.insert(index, process);
+    }
+
+    fn pop(&mut self) -> Option<Pcb<Server>> {
+        self.reserved
+            .pop_front()
+            .or_else(|| self.others.pop_front())
+    }
+
+    fn remove(&mut self, pid: Pid) -> Option<Pcb<Server>> {
+        for queue in [&mut self.reserved, &mut self.others] {
+            if let Some(index) = queue.iter().position(|process| process.pid == pid) {
+                return queue.remove(index);
+            }
+        }
+        None
+    }
+
+    fn preempts(&self, running: &Pcb<Server>) -> bool {
+        self.reserved.front().is_some_and(|first| {
+            running.ext.reservation.is_none() || first.ext.deadline < running.ext.deadline
+        })
+    }
+
+    // a server runs until its budget is used
+    fn timeslice(&self, process: &Pcb<Server>) -> NonZeroUsize {
+        match process.ext.reservation {
+            Some(_) if self.servers => self
+                .timeslice
+                .min(NonZeroUsize::new(process.ext.budget).unwrap_or(NonZeroUsize::MIN)),
+            _ => self.timeslice,
+        }
+    }
+
+    // a server that has used its budget gets it back with the deadline of
+    // its next period, so it cannot delay the others
+    fn charge(&mut self, process: &mut Pcb<Server>, time: usize, _now: usize) {
+        let Some(reservation) = process.ext.reservation.filter(|_| self.servers) else {
+            return;
+        };
+        let server = &mut process.ext;
+        server.budget = server.budget.saturating_sub(time);
+        if server.budget == 0 {
+            server.budget = reservation.runtime.get();
+            server.deadline += reservation.period.get();
+        }
+    }
+
+    fn release(&mut self, now: usize) {
+        self.now = now;
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = &Pcb<Server>> + '_> {
+        Box::new(self.reserved.iter().chain(self.others.iter()))
+    }
+
+    fn iter_mut(&mut self) -> Box<dyn Iterator<Item = &mut Pcb<Server>> + '_> {
+        Box::new(self.reserved.iter_mut().chain(self.others.iter_mut()))
+    }
+}
+
+impl PcbExt for Server {
+    fn extra(&self) -> String {
+        match self.reservation {
+            Some(reservation) => format!(
+                "{reservation} deadline {} budget {}",
+                self.deadline, self.budget
+            ),
+            None => String::new(),
+        }
+    }
+}
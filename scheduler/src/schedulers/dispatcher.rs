@@ -0,0 +1,449 @@
+use std::collections::HashSet;
+use std::num::NonZeroUsize;
+
+use crate::clock::Clock;
+use crate::groups::ThrottleEvent;
+use crate::pcb::{Pcb, PcbExt};
+use crate::scheduler::{
+    PanicReason, Pid, Process, ProcessState, Scheduler, SchedulingDecision, StopReason, Syscall,
+    SyscallResult,
+};
+use crate::sleep_queue::SleepQueue;
+use crate::wait_queues::WaitQueues;
+
+/// Why a process becomes ready.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Requeue {
+    /// The process was forked, woken up or its attributes have changed.
+    Ready,
+
+    /// The running process has used its timeslice, or too much of it to
+    /// go on after a system call.
+    Expired,
+
+    /// The running process was about to go on after a system call, but
+    /// the policy prefers another process.
+    Preempted,
+
+    /// The running process has given up the rest of its timeslice.
+    Yielded,
+}
+
+/// The part of a scheduler that orders the ready processes. The
+/// [`Dispatcher`] handles the rest of the life of a process.
+pub trait Policy: Send {
+    /// The policy specific data of a process.
+    type Ext: PcbExt;
+
+    /// The data of a process created by `parent` with a [`Syscall::Fork`], a
+    /// [`Syscall::ForkClass`] or a [`Syscall::ForkReserved`].
+    fn fork(&mut self, parent: Option<&Pcb<Self::Ext>>, syscall: Syscall) -> Self::Ext;
+
+    /// Whether the process created by the fork can be served together with
+    /// the ready processes and the `others`.
+    fn admits<'a>(
+        &self,
+        _syscall: Syscall,
+        _others: impl Iterator<Item = &'a Pcb<Self::Ext>>,
+    ) -> bool
+    where
+        Self::Ext: 'a,
+    {
+        true
+    }
+
+    /// Add a ready process.
+    fn push(&mut self, process: Pcb<Self::Ext>, requeue: Requeue);
+
+    /// Remove the process that runs next.
+    fn pop(&mut self) -> Option<Pcb<Self::Ext>>;
+
+    /// Remove a ready process.
+    fn remove(&mut self, pid: Pid) -> Option<Pcb<Self::Ext>>;
+
+    /// Whether a ready process should run instead of the process that goes
+    /// on after a system call.
+    fn preempts(&self, running: &Pcb<Self::Ext>) -> bool;
+
+    /// The timeslice of a process that is scheduled.
+    fn timeslice(&self, process: &Pcb<Self::Ext>) -> NonZeroUsize;
+
+    /// Account the `time` used by the running process, that has stopped
+    /// at `now`.
+    fn charge(&mut self, _process: &mut Pcb<Self::Ext>, _time: usize, _now: usize) {}
+
+    /// Let the time pass until `now`, for the policies that hold back
+    /// ready processes until some time or that need the time when a
+    /// process becomes ready. It is called before the sleepers that wake
+    /// up are pushed.
+    fn release(&mut self, _now: usize) {}
+
+    /// The time from `now` until a process that is held back becomes ready.
+    fn next_release(&self, _now: usize) -> Option<NonZeroUsize> {
+        None
+    }
+
+    /// Take the [`ThrottleEvent`]s recorded since the last call.
+    fn throttle_events(&mut self) -> Vec<ThrottleEvent> {
+        vec![]
+    }
+
+    /// Apply a [`Syscall::SetPriority`], a [`Syscall::SetClass`] or a
+    /// [`Syscall::SetGroup`] to a process that is not ready, the caller is
+    /// allowed to change it.
+    fn set(&mut self, process: &mut Pcb<Self::Ext>, syscall: Syscall) -> SyscallResult {
+        match syscall {
+            Syscall::SetPriority(_, priority) => process.priority = priority,
+            Syscall::SetGroup(_, group) => process.group = group,
+            _ => {}
+        }
+        SyscallResult::Success
+    }
+
+    /// Update the data of the ready processes and of the `others`, before
+    /// they are listed.
+    fn refresh<'a>(&mut self, _others: impl Iterator<Item = &'a mut Pcb<Self::Ext>>)
+    where
+        Self::Ext: 'a,
+    {
+    }
+
+    /// The ready processes.
+    fn iter(&self) -> Box<dyn Iterator<Item = &Pcb<Self::Ext>> + '_>;
+
+    /// The ready processes.
+    fn iter_mut(&mut self) -> Box<dyn Iterator<Item = &mut Pcb<Self::Ext>> + '_>;
+}
+
+/// A scheduler made of a [`Policy`] for the ready processes and of the
+/// handling of the system calls that every policy shares: the running
+/// process goes on after a system call for the rest of its timeslice if
+/// at least `minimum_remaining_timeslice` is left and the policy does not
+/// prefer another process.
+pub struct Dispatcher<P: Policy> {
+    pub policy: P,
+    /// The scheduled process and the timeslice it has left.
+    pub running: Option<(Pcb<P::Ext>, NonZeroUsize)>,
+    pub wait_q: WaitQueues<P::Ext>,
+    pub sleep_q: SleepQueue<P::Ext>,
+    pub minimum_remaining_timeslice: usize,
+    pub clock: Clock,
+    pub last_pid: usize,
+    pub panic: Option<PanicReason>,
+    pub latched_events: HashSet<usize>,
+}
+
+impl<P: Policy> Dispatcher<P> {
+    pub fn new(policy: P, minimum_remaining_timeslice: usize) -> Dispatcher<P> {
+        Dispatcher {
+            policy,
+            running: None,
+            wait_q: WaitQueues::default(),
+            sleep_q: SleepQueue::default(),
+            minimum_remaining_timeslice,
+            clock: Clock::default(),
+            last_pid: 0,
+            panic: None,
+            latched_events: HashSet::new(),
+        }
+    }
+
+    fn alive(&self) -> usize {
+        usize::from(self.running.is_some())
+            + self.policy.iter().count()
+            + self.wait_q.len()
+            + self.sleep_q.len()
+    }
+
+    /// Account the time used by the running process and let it pass for
+    /// all the processes, the sleepers whose time has come become ready.
+    fn elapse(&mut self, time: usize, syscall: bool) {
+        self.resume();
+        if let Some((process, _)) = self.running.as_mut() {
+            self.clock.run(process, time, syscall);
+            self.policy.charge(process, time, self.clock.now() + time);
+        }
+        self.clock.advance(
+            time,
+            self.running
+                .iter_mut()
+                .map(|(process, _)| process)
+                .chain(self.policy.iter_mut())
+                .chain(self.wait_q.iter_mut()),
+        );
+        self.wake_sleepers();
+    }
+
+    /// Let the time the processor has slept pass for all the processes.
+    fn resume(&mut self) {
+        self.clock.resume(
+            self.running
+                .iter_mut()
+                .map(|(process, _)| process)
+                .chain(self.policy.iter_mut())
+                .chain(self.wait_q.iter_mut()),
+        );
+    }
+
+    fn wake_sleepers(&mut self) {
+        self.policy.release(self.clock.now());
+        for process in self.clock.wake(&mut self.sleep_q) {
+            self.policy.push(process, Requeue::Ready);
+        }
+    }
+
+    /// Give the running process back to the policy.
+    fn requeue(&mut self, requeue: Requeue) {
+        if let Some((mut process, _)) = self.running.take() {
+            process.state = ProcessState::Ready;
+            self.policy.push(process, requeue);
+        }
+    }
+
+    /// After a system call that does not block, the running process goes on
+    /// for the rest of its timeslice if at least `minimum_remaining_timeslice`
+    /// is left, otherwise it waits for its turn.
+    fn replan(&mut self, remaining: usize) {
+        match NonZeroUsize::new(remaining).filter(|_| remaining >= self.minimum_remaining_timeslice)
+        {
+            Some(remaining) => {
+                if let Some((_, timeslice)) = self.running.as_mut() {
+                    *timeslice = remaining;
+                }
+            }
+            None => self.requeue(Requeue::Expired),
+        }
+    }
+
+    fn fork(&mut self, syscall: Syscall) -> SyscallResult {
+        let priority = match syscall {
+            Syscall::ForkClass { priority, .. } => priority,
+            Syscall::ForkReserved { priority, .. } => priority,
+            Syscall::Fork(priority) => priority,
+            _ => unreachable!("{syscall:?} is not a fork"),
+        };
+        let parent = self.running.as_ref().map(|(process, _)| process);
+        let others = parent
+            .into_iter()
+            .chain(self.wait_q.iter())
+            .chain(self.sleep_q.iter());
+        if !self.policy.admits(syscall, others) {
+            return SyscallResult::AdmissionDenied;
+        }
+        let ext = self.policy.fork(parent, syscall);
+        self.last_pid += 1;
+        let mut process = Pcb::new(
+            Pid::new(self.last_pid),
+            parent.map(|process| process.pid),
+            priority,
+            ext,
+        );
+        process.group = parent.map_or(0, |parent| parent.group);
+        let pid = process.pid;
+        self.policy.push(process, Requeue::Ready);
+        SyscallResult::Pid(pid)
+    }
+
+    /// Change the priority, the class or the group of the running process
+    /// or of one of its children.
+    fn set(&mut self, pid: Pid, syscall: Syscall) -> SyscallResult {
+        let Some((caller, _)) = self.running.as_mut() else {
+            return SyscallResult::NoRunningProcess;
+        };
+        if caller.pid == pid {
+            return self.policy.set(caller, syscall);
+        }
+        let caller = caller.pid;
+
+        // a ready process is queued again, with its new attributes
+        if let Some(mut process) = self.policy.remove(pid) {
+            let result = if process.parent == Some(caller) {
+                self.policy.set(&mut process, syscall)
+            } else {
+                SyscallResult::PermissionDenied
+            };
+            self.policy.push(process, Requeue::Ready);
+            return result;
+        }
+
+        let process = self
+            .wait_q
+            .iter_mut()
+            .chain(self.sleep_q.iter_mut())
+            .find(|process| process.pid == pid);
+        match process {
+            None => SyscallResult::InvalidPid,
+            Some(process) if process.parent != Some(caller) => SyscallResult::PermissionDenied,
+            Some(process) => self.policy.set(process, syscall),
+        }
+    }
+}
+
+impl<P: Policy> Scheduler for Dispatcher<P> {
+    fn next(&mut self) -> SchedulingDecision {
+        if self.last_pid == 0 {
+            return SchedulingDecision::Panic(PanicReason::NoInit);
+        }
+        if let Some(reason) = self.panic {
+            return SchedulingDecision::Panic(reason);
+        }
+
+        self.resume();
+        self.wake_sleepers();
+
+        if let Some((process, timeslice)) = self.running.as_mut() {
+            if !self.policy.preempts(process) {
+                *timeslice = (*timeslice).min(self.policy.timeslice(process));
+                return SchedulingDecision::Run {
+                    pid: process.pid,
+                    timeslice: *timeslice,
+                };
+            }
+            self.requeue(Requeue::Preempted);
+        }
+
+        if let Some(mut process) = self.policy.pop() {
+            process.state = ProcessState::Running;
+            process.waited = 0;
+            let pid = process.pid;
+            let timeslice = self.policy.timeslice(&process);
+            self.running = Some((process, timeslice));
+            return SchedulingDecision::Run { pid, timeslice };
+        }
+
+        // the processor sleeps until a sleeper wakes up or the policy
+        // releases a process that it holds back
+        let wakeup = self.clock.next_wakeup(&self.sleep_q);
+        let release = self.policy.next_release(self.clock.now());
+        match wakeup.into_iter().chain(release).min() {
+            Some(time) => {
+                self.clock.sleep(time.get());
+                SchedulingDecision::Sleep(time)
+            }
+            None if !self.wait_q.is_empty() => SchedulingDecision::Deadlock,
+            None => SchedulingDecision::Done,
+        }
+    }
+
+    fn stop(&mut self, reason: StopReason) -> SyscallResult {
+        let StopReason::Syscall { syscall, remaining } = reason else {
+            let Some((_, timeslice)) = self.running else {
+                return SyscallResult::NoRunningProcess;
+            };
+            self.elapse(timeslice.get(), false);
+            self.requeue(Requeue::Expired);
+            return SyscallResult::Success;
+        };
+
+        // the first process is forked while no process runs
+        let fork = matches!(
+            syscall,
+            Syscall::Fork(_) | Syscall::ForkClass { .. } | Syscall::ForkReserved { .. }
+        );
+        let timeslice = match self.running {
+            Some((_, timeslice)) if remaining >= timeslice.get() => {
+                return SyscallResult::InvalidArgument
+            }
+            Some((_, timeslice)) => timeslice.get(),
+            None if fork => remaining,
+            None => return SyscallResult::NoRunningProcess,
+        };
+        self.elapse(timeslice - remaining, true);
+
+        match syscall {
+            Syscall::Fork(_) | Syscall::ForkClass { .. } | Syscall::ForkReserved { .. } => {
+                let result = self.fork(syscall);
+                self.replan(remaining);
+                result
+            }
+            Syscall::Wait(event) => {
+                // a latched event is consumed and the process does not block
+                if self.latched_events.remove(&event) {
+                    self.replan(remaining);
+                } else {
+                    let (process, _) = self.running.take().unwrap();
+                    self.wait_q.push(event, process);
+                }
+                SyscallResult::Success
+            }
+            Syscall::Sleep(_) | Syscall::Io { .. } => {
+                let time = syscall.blocking_time().unwrap_or_default();
+                let (mut process, _) = self.running.take().unwrap();
+                process.state = ProcessState::Waiting { event: None };
+                process.wakeup = self.clock.wakeup(time);
+                self.sleep_q.push(process, self.clock.now());
+                SyscallResult::Success
+            }
+            Syscall::Signal(event) | Syscall::SignalOne(event) | Syscall::SignalLatched(event) => {
+                let limit = match syscall {
+                    Syscall::SignalOne(_) => 1,
+                    _ => usize::MAX,
+                };
+                let woken = self.wait_q.wake(event, limit);
+                let count = woken.len();
+                for process in woken {
+                    self.policy.push(process, Requeue::Ready);
+                }
+                if count == 0 && matches!(syscall, Syscall::SignalLatched(_)) {
+                    self.latched_events.insert(event);
+                }
+                self.replan(remaining);
+                SyscallResult::Woken(count)
+            }
+            Syscall::SetPriority(pid, _)
+            | Syscall::SetClass(pid, _)
+            | Syscall::SetGroup(pid, _) => {
+                let result = self.set(pid, syscall);
+                self.replan(remaining);
+                result
+            }
+            Syscall::Yield => {
+                self.requeue(Requeue::Yielded);
+                SyscallResult::Success
+            }
+            Syscall::Exit => {
+                let (process, _) = self.running.take().unwrap();
+                if process.pid == 1 && self.alive() > 0 {
+                    self.panic = Some(PanicReason::InitExited {
+                        alive: self.alive(),
+                    });
+                }
+                SyscallResult::Success
+            }
+        }
+    }
+
+    fn list(&mut self) -> Vec<&dyn Process> {
+        self.sleep_q.update(self.clock.now());
+        self.policy.refresh(
+            self.running
+                .iter_mut()
+                .map(|(process, _)| process)
+                .chain(self.wait_q.iter_mut())
+                .chain(self.sleep_q.iter_mut()),
+        );
+
+        let mut processes = self
+            .running
+            .iter()
+            .map(|(process, _)| process as &dyn Process)
+            .chain(self.policy.iter().map(|process| process as &dyn Process))
+            .chain(self.wait_q.iter().map(|process| process as &dyn Process))
+            .chain(self.sleep_q.processes())
+            .collect::<Vec<&dyn Process>>();
+        processes.sort_by_key(|process| process.pid());
+        processes
+    }
+
+    fn context_switch(&mut self, time: usize) {
+        self.clock.sleep(time);
+    }
+
+    fn io_completion(&mut self, pid: Pid, wakeup: usize) {
+        self.sleep_q.set_wakeup(pid, wakeup);
+    }
+
+    fn throttle_events(&mut self) -> Vec<ThrottleEvent> {
+        self.policy.throttle_events()
+    }
+}
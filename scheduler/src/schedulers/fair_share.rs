@@ -0,0 +1,218 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::num::NonZeroUsize;
+
+use crate::groups::{GroupConfig, ThrottleEvent};
+use crate::pcb::Pcb;
+use crate::scheduler::{Pid, ProcessState, Syscall};
+use crate::schedulers::dispatcher::{Dispatcher, Policy, Requeue};
+
+/// The virtual time that a group with a weight of 1 is charged for every
+/// time unit, heavier groups are charged proportionally less.
+const VIRTUAL_UNIT: usize = 1 << 20;
+
+/// Group fair-share scheduler: the group with ready processes that has
+/// used the least processor time relative to its weight runs next, and its
+/// ready processes take turns like in a round robin. A group that has used
+/// its [`Bandwidth`](crate::Bandwidth) quota is throttled until its next
+/// period.
+pub type GroupFairShare = Dispatcher<FairShare>;
+
+/// The ready processes of a group.
+#[derive(Debug, Default)]
+pub struct Group {
+    /// The ready processes, in the order they became ready.
+    pub ready: VecDeque<Pcb>,
+
+    /// The processes held back until the next period, in the order they
+    /// became ready.
+    pub throttled: VecDeque<Pcb>,
+
+    /// The processor time used by the group, divided by its weight.
+    pub vruntime: usize,
+
+    /// The current period of the group's bandwidth.
+    pub period: usize,
+
+    /// The processor time used by the group during the current period.
+    pub used: usize,
+}
+
+/// The ready processes of a [`GroupFairShare`] scheduler, by group.
+pub struct FairShare {
+    pub groups: BTreeMap<usize, Group>,
+    pub config: GroupConfig,
+    pub timeslice: NonZeroUsize,
+    /// The virtual time of the last group that was scheduled, a group that
+    /// becomes ready starts from it, so it cannot claim the time it has
+    /// not used while it had nothing to run.
+    pub min_vruntime: usize,
+    /// The time of the last release.
+    pub now: usize,
+    /// The groups throttled and released since the events were taken.
+    pub throttle_events: Vec<ThrottleEvent>,
+}
+
+impl FairShare {
+    pub fn new(timeslice: NonZeroUsize, config: GroupConfig) -> FairShare {
+        FairShare {
+            groups: BTreeMap::new(),
+            config,
+            timeslice,
+            min_vruntime: 0,
+            now: 0,
+            throttle_events: vec![],
+        }
+    }
+
+    /// The processor time a group can still use during the current period,
+    /// `None` if the group is not limited.
+    fn left(&self, group: usize) -> Option<usize> {
+        let bandwidth = self.config.bandwidth.get(&group)?;
+        let used = self.groups.get(&group).map_or(0, |group| group.used);
+        Some(bandwidth.quota.get().saturating_sub(used))
+    }
+}
+
+impl Policy for FairShare {
+    type Ext = ();
+
+    fn fork(&mut self, _parent: Option<&Pcb>, _syscall: Syscall) {}
+
+    fn push(&mut self, mut process: Pcb, _requeue: Requeue) {
+        let throttled = self.left(process.group) == Some(0);
+        let group = self.groups.entry(process.group).or_default();
+        if throttled {
+            process.state = ProcessState::Throttled;
+            group.throttled.push_back(process);
+            return;
+        }
+        if group.ready.is_empty() {
+            group.vruntime = group.vruntime.max(self.min_vruntime);
+        }
+        group.ready.push_back(process);
+    }
+
+    fn pop(&mut self) -> Option<Pcb> {
+        let group = self
+            .groups
+            .values_mut()
+            .filter(|group| !group.ready.is_empty())
+            .min_by_key(|group| group.vruntime)?;
+        self.min_vruntime = self.min_vruntime.max(group.vruntime);
+        group.ready.pop_front()
+    }
+
+    fn remove(&mut self, pid: Pid) -> Option<Pcb> {
+        self.groups.values_mut().find_map(|group| {
+            let queue = if group.ready.iter().any(|process| process.pid == pid) {
+                &mut group.ready
+            } else {
+                &mut group.throttled
+            };
+            let index = queue.iter().position(|process| process.pid == pid)?;
+            queue.remove(index)
+        })
+    }
+
+    // a process whose group has used its quota stops after a system call
+    fn preempts(&self, running: &Pcb) -> bool {
+        self.left(running.group) == Some(0)
+    }
+
+    // a limited group runs until its quota or its period ends
+    fn timeslice(&self, process: &Pcb) -> NonZeroUsize {
+        let Some(bandwidth) = self.config.bandwidth.get(&process.group) else {
+            return self.timeslice;
+        };
+        let left = self.left(process.group).and_then(NonZeroUsize::new);
+        self.timeslice
+            .min(bandwidth.until_next(self.now))
+            .min(left.unwrap_or(NonZeroUsize::MIN))
+    }
+
+    fn charge(&mut self, process: &mut Pcb, time: usize, now: usize) {
+        let throttled = self.left(process.group) == Some(0);
+        let weight = self.config.weight_of(process.group).get();
+        let group = self.groups.entry(process.group).or_default();
+        group.vruntime += time * VIRTUAL_UNIT / weight;
+        group.used += time;
+        // the ready processes of the group wait for the next period
+        if self.left(process.group) == Some(0) {
+            let group = self.groups.get_mut(&process.group).unwrap();
+            if !throttled {
+                self.throttle_events.push(ThrottleEvent::Throttled {
+                    group: process.group,
+                    time: now,
+                    used: group.used,
+                });
+            }
+            for mut process in group.ready.drain(..) {
+                process.state = ProcessState::Throttled;
+                group.throttled.push_back(process);
+            }
+        }
+    }
+
+    // the groups that start a new period get their quota back
+    fn release(&mut self, now: usize) {
+        self.now = now;
+        for (number, bandwidth) in self.config.bandwidth.iter() {
+            let Some(group) = self.groups.get_mut(number) else {
+                continue;
+            };
+            let period = bandwidth.period_of(now);
+            if group.period == period {
+                continue;
+            }
+            if group.used >= bandwidth.quota.get() {
+                self.throttle_events.push(ThrottleEvent::Released {
+                    group: *number,
+                    time: now,
+                    used: group.used,
+                });
+            }
+            group.period = period;
+            group.used = 0;
+            if !group.throttled.is_empty() && group.ready.is_empty() {
+                group.vruntime = group.vruntime.max(self.min_vruntime);
+            }
+            for mut process in group.throttled.drain(..) {
+                process.state = ProcessState::Ready;
+                group.ready.push_back(process);
+            }
+        }
+    }
+
+    fn next_release(&self, now: usize) -> Option<NonZeroUsize> {
+        self.config
+            .bandwidth
+            .iter()
+            .filter(|(number, _)| {
+                self.groups
+                    .get(number)
+                    .is_some_and(|group| !group.throttled.is_empty())
+            })
+            .map(|(_, bandwidth)| bandwidth.until_next(now))
+            .min()
+    }
+
+    fn throttle_events(&mut self) -> Vec<ThrottleEvent> {
+        std::mem::take(&mut self.throttle_events)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = &Pcb> + '_> {
+        Box::new(
+            self.groups
+                .values()
+                .flat_map(|group| group.ready.iter().chain(group.throttled.iter())),
+        )
+    }
+
+    fn iter_mut(&mut self) -> Box<dyn Iterator<Item = &mut Pcb> + '_> {
+        Box::new(
+            self.groups
+                .values_mut()
+                .flat_map(|group| group.ready.iter_mut().chain(group.throttled.iter_mut())),
+        )
+    }
+}
@@ -1,3 +1,14 @@
 // import schedulers
+mod deadlines;
+mod dispatcher;
+mod fair_share;
+mod priority_queue;
+mod rate_monotonic;
 mod round_robin;
+mod rt_classes;
+pub use deadlines::{DeadlineScheduler, Deadlines};
+pub use fair_share::{FairShare, GroupFairShare};
+pub use priority_queue::{Priorities, PriorityQueue};
+pub use rate_monotonic::{Periods, RateMonotonic};
 pub use round_robin::RoundRobin;
+pub use rt_classes::{Classes, RtClasses};
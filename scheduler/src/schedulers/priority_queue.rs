@@ -0,0 +1,93 @@
+use std::collections::VecDeque;
+use std::num::NonZeroUsize;
+
+use crate::aging::{Aged, Aging};
+use crate::pcb::Pcb;
+use crate::scheduler::{Pid, Syscall};
+use crate::schedulers::dispatcher::{Dispatcher, Policy, Requeue};
+
+/// Priority scheduler: the ready process with the largest effective priority
+/// runs first, processes with the same priority take turns like in a round
+/// robin. The effective priority grows with the time a process waits, as
+/// set by its [`Aging`].
+pub type PriorityQueue = Dispatcher<Priorities>;
+
+/// The ready processes of a [`PriorityQueue`].
+pub struct Priorities {
+    /// The ready processes, in the order they became ready.
+    pub ready: VecDeque<Pcb<Aged>>,
+    pub timeslice: NonZeroUsize,
+    pub aging: Aging,
+}
+
+impl Priorities {
+    pub fn new(timeslice: NonZeroUsize, aging: Aging) -> Priorities {
+        Priorities {
+            ready: VecDeque::new(),
+            timeslice,
+            aging,
+        }
+    }
+
+    /// The position of the ready process with the largest effective priority,
+    /// the first one to become ready among equals.
+    fn best(&self) -> Option<usize> {
+        let mut best: Option<(usize, i8)> = None;
+        for (index, process) in self.ready.iter().enumerate() {
+            let priority = self.aging.priority(process);
+            if best.is_none_or(|(_, best)| priority > best) {
+                best = Some((index, priority));
+            }
+        }
+        best.map(|(index, _)| index)
+    }
+}
+
+impl Policy for Priorities {
+    type Ext = Aged;
+
+    fn fork(&mut self, _parent: Option<&Pcb<Aged>>, _syscall: Syscall) -> Aged {
+        Aged::default()
+    }
+
+    fn push(&mut self, process: Pcb<Aged>, _requeue: Requeue) {
+        self.ready.push_back(process);
+    }
+
+    fn pop(&mut self) -> Option<Pcb<Aged>> {
+        self.best().and_then(|best| self.ready.remove(best))
+    }
+
+    fn remove(&mut self, pid: Pid) -> Option<Pcb<Aged>> {
+        let index = self.ready.iter().position(|process| process.pid == pid)?;
+        self.ready.remove(index)
+    }
+
+    // a process that goes on after a system call keeps the processor,
+    // unless a process with a larger priority is ready
+    fn preempts(&self, running: &Pcb<Aged>) -> bool {
+        self.best()
+            .is_some_and(|best| self.aging.priority(&self.ready[best]) > running.priority)
+    }
+
+    fn timeslice(&self, _process: &Pcb<Aged>) -> NonZeroUsize {
+        self.timeslice
+    }
+
+    fn refresh<'a>(&mut self, others: impl Iterator<Item = &'a mut Pcb<Aged>>) {
+        for process in self.ready.iter_mut() {
+            self.aging.update(process);
+        }
+        for process in others {
+            self.aging.update(process);
+        }
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = &Pcb<Aged>> + '_> {
+        Box::new(self.ready.iter())
+    }
+
+    fn iter_mut(&mut self) -> Box<dyn Iterator<Item = &mut Pcb<Aged>> + '_> {
+        Box::new(self.ready.iter_mut())
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::VecDeque;
+use std::num::NonZeroUsize;
+
+use crate::pcb::{Pcb, PcbExt};
+use crate::reservation::Reservation;
+use crate::scheduler::{Pid, Syscall};
+use crate::schedulers::dispatcher::{Dispatcher, Policy, Requeue};
+
+/// Rate monotonic scheduler: the ready periodic processes run by increasing
+/// period, before the processes without a [`Reservation`], that take turns
+/// like in a round robin.
+pub type RateMonotonic = Dispatcher<Periods>;
+
+/// The ready processes of a [`RateMonotonic`] scheduler.
+pub struct Periods {
+    /// The ready periodic processes, by period.
+    pub periodic: VecDeque<Pcb<Option<Reservation>>>,
+    /// The ready processes without a period, in the order they became ready.
+    pub others: VecDeque<Pcb<Option<Reservation>>>,
+    pub timeslice: NonZeroUsize,
+}
+
+impl Periods {
+    pub fn new(timeslice: NonZeroUsize) -> Periods {
+        Periods {
+            periodic: VecDeque::new(),
+            others: VecDeque::new(),
+            timeslice,
+        }
+    }
+
+    fn period(process: &Pcb<Option<Reservation>>) -> Option<NonZeroUsize> {
+        process.ext.map(|reservation| reservation.period)
+    }
+}
+
+impl Policy for Periods {
+    type Ext = Option<Reservation>;
+
+    // only the period of the reservation is used, it is not inherited
+    fn fork(
+        &mut self,
+        _parent: Option<&Pcb<Option<Reservation>>>,
+        syscall: Syscall,
+    ) -> Option<Reservation> {
+        match syscall {
+            Syscall::ForkReserved { reservation, .. } => Some(reservation),
+            _ => None,
+        }
+    }
+
+    fn push(&mut self, process: Pcb<Option<Reservation>>, requeue: Requeue) {
+        let Some(period) = Periods::period(&process) else {
+            self.others.push_back(process);
+            return;
+        };
+        // a preempted process goes on before the others with its period
+        let index = self.periodic.partition_point(|other| {
+            let other = Periods::period(other).unwrap();
+            match requeue {
+                Requeue::Preempted => other < period,
+                _ => other <= period,
+            }
+        });
+        self.periodic.insert(index, process);
+    }
+
+    fn pop(&mut self) -> Option<Pcb<Option<Reservation>>> {
+        self.periodic
+            .pop_front()
+            .or_else(|| self.others.pop_front())
+    }
+
+    fn remove(&mut self, pid: Pid) -> Option<Pcb<Option<Reservation>>> {
+        for queue in [&mut self.periodic, &mut self.others] {
+            if let Some(index) = queue.iter().position(|process| process.pid == pid) {
+                return queue.remove(index);
+            }
+        }
+        None
+    }
+
+    fn preempts(&self, running: &Pcb<Option<Reservation>>) -> bool {
+        self.periodic
+            .front()
+            .is_some_and(|first| match Periods::period(running) {
+                Some(period) => Periods::period(first).unwrap() < period,
+                None => true,
+            })
+    }
+
+    fn timeslice(&self, _process: &Pcb<Option<Reservation>>) -> NonZeroUsize {
+        self.timeslice
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = &Pcb<Option<Reservation>>> + '_> {
+        Box::new(self.periodic.iter().chain(self.others.iter()))
+    }
+
+    fn iter_mut(&mut self) -> Box<dyn Iterator<Item = &mut Pcb<Option<Reservation>>> + '_> {
+        Box::new(self.periodic.iter_mut().chain(self.others.iter_mut()))
+    }
+}
+
+impl PcbExt for Option<Reservation> {
+    fn extra(&self) -> String {
+        self.map(|reservation| reservation.to_string())
+            .unwrap_or_default()
+    }
+}
@@ -1,78 +1,62 @@
 use std::num::NonZeroUsize;
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 
+use crate::clock::Clock;
+use crate::pcb::Pcb;
 pub use crate::scheduler::{
-	Pid, Process, ProcessState, Scheduler, SchedulingDecision, StopReason, Syscall, SyscallResult,
+	PanicReason, Pid, Process, ProcessState, Scheduler, StopReason, Syscall, SyscallResult,
 };
-
-#[derive(Clone)]
-pub struct ProcessInfo {
-	pub pid: Pid,
-	pub state: ProcessState,
-	pub timings: (usize, usize, usize),
-	pub priority: i8,
-	pub sleep_time: usize,
-	pub extra: String,
-}
-
-impl Process for ProcessInfo {
-	fn pid(&self) -> Pid {
-		self.pid
-	}
-
-	fn state(&self) -> ProcessState {
-		self.state
-	}
-
-	fn timings(&self) -> (usize, usize, usize) {
-		self.timings
-	}
-
-	fn priority(&self) -> i8 {
-		self.priority
-	}
-
-	fn extra(&self) -> String {
-		self.extra.clone()
-	}
-}
+use crate::sleep_queue::SleepQueue;
+use crate::wait_queues::WaitQueues;
 
 /// Round Robin scheduler struct
 pub struct RoundRobin {
-	pub ready_q: VecDeque<ProcessInfo>,
-	pub wait_q: VecDeque<ProcessInfo>,
-	pub sleep_q: VecDeque<ProcessInfo>,
+	pub ready_q: VecDeque<Pcb>,
+	pub wait_q: WaitQueues,
+	pub sleep_q: SleepQueue,
 	pub timeslice: NonZeroUsize,
 	pub minimum_remaining_timeslice: usize,
 	pub init_pid: usize,
-	pub panic_state: bool,
-	pub sleep_time: usize,
+	pub panic: Option<PanicReason>,
+	pub adopt_orphans: bool,
+	pub clock: Clock,
 	pub default_timeslice: NonZeroUsize,
+	pub latched_events: HashSet<usize>,
 }
 
 impl RoundRobin {
-	/// Verify if the proc pid 1 is the last to exit
-	fn panic_verify(&self) -> bool {
-		let mut init_proc: bool = false;
-		for p in self.ready_q.iter() {
-			if p.pid() == 1 {
-				init_proc = true;
-			}
+	/// The reason to panic, if no process was ever forked or if the proc
+	/// pid 1 has exited before the others
+	fn panic_verify(&self) -> Option<PanicReason> {
+		if self.init_pid == 0 {
+			return Some(PanicReason::NoInit);
 		}
 
-		for p in self.wait_q.iter() {
-			if p.pid() == 1 {
-				init_proc = true;
-			}
-		}
+		self.panic
+	}
 
-		for p in self.sleep_q.iter() {
-			if p.pid() == 1 {
-				init_proc = true;
+	/// The number of processes in all the queues
+	fn alive(&self) -> usize {
+		self.ready_q.len() + self.wait_q.len() + self.sleep_q.len()
+	}
+
+	/// Gives the children of an exited process to the proc pid 1, or to
+	/// no process if the proc pid 1 has exited too
+	fn reparent_children(&mut self, parent: Pid) {
+		let init = Pid::new(1);
+		let adopter = if parent != init && self.find_mut(init).is_some() {
+			Some(init)
+		} else {
+			None
+		};
+
+		for proc in self.ready_q.iter_mut()
+			.chain(self.wait_q.iter_mut())
+			.chain(self.sleep_q.iter_mut()) {
+			if proc.parent == Some(parent) {
+				proc.parent = adopter;
 			}
 		}
-
-		!init_proc && self.panic_state
 	}
 
 	/// Verifies if there are only waiting processes
@@ -85,14 +69,74 @@ impl RoundRobin {
 
 		false
 	}
+
+	/// Moves at most `limit` processes waiting for `event` to the ready_q,
+	/// in the order they started waiting, and returns how many were woken up
+	fn wake_waiters(&mut self, event: usize, limit: usize) -> usize {
+		let woken = self.wait_q.wake(event, limit);
+		let count = woken.len();
+		self.ready_q.extend(woken);
+
+		count
+	}
+
+	/// Plans again the process that sent a non blocking syscall for the remaining
+	/// time of its quanta, or moves it to the back of the ready_q if the remaining
+	/// time is smaller than minimum_remaining_timeslice
+	fn replan(&mut self, mut proc: Pcb, remaining: usize) {
+		proc.state = ProcessState::Ready;
+
+		match self.continues(remaining) {
+			Some(timeslice) => {
+				self.timeslice = timeslice;
+				self.ready_q.push_front(proc);
+			},
+			None => {
+				self.timeslice = self.default_timeslice;
+				self.ready_q.push_back(proc);
+			},
+		}
+	}
+
+	/// The timeslice a process continues with after a syscall, if the
+	/// remaining time is not 0 and at least minimum_remaining_timeslice
+	fn continues(&self, remaining: usize) -> Option<NonZeroUsize> {
+		NonZeroUsize::new(remaining).filter(|_| remaining >= self.minimum_remaining_timeslice)
+	}
+
+	/// Accounts the time used by the running process, that is on the front
+	/// of the ready_q, lets the time pass for all processes and moves the
+	/// sleeping processes that woke up to the ready_q
+	fn elapse(&mut self, time: usize, syscall: bool) {
+		self.clock.resume(self.ready_q.iter_mut()
+			.chain(self.wait_q.iter_mut()));
+
+		if let Some(proc) = self.ready_q.front_mut() {
+			self.clock.run(proc, time, syscall);
+		}
+		// the sleep_q adds the time to the sleeping processes when they wake up
+		self.clock.advance(time, self.ready_q.iter_mut()
+			.chain(self.wait_q.iter_mut()));
+
+		let woken = self.clock.wake(&mut self.sleep_q);
+		self.ready_q.extend(woken);
+	}
+
+	/// Finds a process that is not running in any of the queues
+	fn find_mut(&mut self, pid: Pid) -> Option<&mut Pcb> {
+		self.ready_q.iter_mut()
+			.chain(self.wait_q.iter_mut())
+			.chain(self.sleep_q.iter_mut())
+			.find(|p| p.pid == pid)
+	}
 }
 
 impl Scheduler for RoundRobin {
 	/// Using RoundRobin fields, a scheduling decision is returned
 	fn next(&mut self) -> crate::SchedulingDecision {
 		// the scheduler should be in a valid state
-		if self.panic_verify() {
-			return crate::SchedulingDecision::Panic;
+		if let Some(reason) = self.panic_verify() {
+			return crate::SchedulingDecision::Panic(reason);
 		}
 
 		// the scheduler should not run in an infinite loop
@@ -106,47 +150,20 @@ impl Scheduler for RoundRobin {
 			return crate::SchedulingDecision::Done;
 		}
 
-		// scheduler sleep time is added to waiting processes
-		for p in self.wait_q.iter_mut() {
-			p.timings.0 += self.sleep_time;
-		}
-
-		// the scheduler sleep time should be minimum for efficiency
-		let mut min_sleep_time: usize = 1000000;
-		for p in self.sleep_q.iter_mut() {
-			// retaining previous scheduler sleep_time (possibly 0)
-			p.timings.0 += self.sleep_time;
-
-			// eventually find some ready processes to put in the queue
-			if p.sleep_time == 0 {
-				// process state changed here
-				p.state = ProcessState::Ready;
-				// no multiple mutable references allowed
-				self.ready_q.push_back((*p).clone());
-			}
+		// the time the scheduler has slept passes for all processes
+		self.clock.resume(self.ready_q.iter_mut()
+			.chain(self.wait_q.iter_mut()));
 
-			if min_sleep_time > p.sleep_time {
-				min_sleep_time = p.sleep_time;
-			}
-		}
-
-		// p.sleep_time = 0, then p is in ready_q
-		self.sleep_q.retain(|p| p.sleep_time > 0);
+		// eventually find some ready processes to put in the queue
+		let woken = self.clock.wake(&mut self.sleep_q);
+		self.ready_q.extend(woken);
 
 		// no ready processes to plan, then put scheduler in sleep state
+		// until the first sleeping process wakes up
 		if self.ready_q.is_empty() {
-			for p in self.sleep_q.iter_mut() {
-				// simulate sleeping time
-				if p.sleep_time >= min_sleep_time {
-					p.sleep_time -= min_sleep_time;
-				} else {
-					p.sleep_time = 0;
-				}
-			}
-
-			// sleeping time to add to waiting processes
-			self.sleep_time = min_sleep_time;
-			return crate::SchedulingDecision::Sleep(NonZeroUsize::new(min_sleep_time).unwrap());
+			let time = self.clock.next_wakeup(&self.sleep_q).unwrap();
+			self.clock.sleep(time.get());
+			return crate::SchedulingDecision::Sleep(time);
 		}
 
 		// if code reaches here, then at least one process is ready or running
@@ -161,6 +178,7 @@ impl Scheduler for RoundRobin {
 		// case for the first fork or a single running process
 		if proc.state == ProcessState::Ready {
 			proc.state = ProcessState::Running;
+			proc.waited = 0;
 			// running process is always on the front of the ready queue
 			self.ready_q.push_front(proc);
 
@@ -176,10 +194,12 @@ impl Scheduler for RoundRobin {
 			proc.state = ProcessState::Ready;
 			self.ready_q.push_back(proc);
 			next_proc.state = ProcessState::Running;
-			self.ready_q.push_front(next_proc.clone());
+			next_proc.waited = 0;
+			let next_pid = next_proc.pid;
+			self.ready_q.push_front(next_proc);
 
 			return crate::SchedulingDecision::Run {
-				pid: next_proc.pid,
+				pid: next_pid,
 				timeslice: self.timeslice,
 			};
 		}
@@ -192,72 +212,45 @@ impl Scheduler for RoundRobin {
 	fn stop(&mut self, reason: crate::StopReason) -> crate::SyscallResult {
 		match reason {
 			StopReason::Syscall { syscall, remaining } => {
-				// time update for sleeping processes
-				for p in self.sleep_q.iter_mut() {
-					p.timings.0 += self.timeslice.get() - remaining;
-
-					// required because sleep_time cannot be negative and it should be usize
-					if p.sleep_time as i32 - ((self.timeslice.get() - remaining) as i32) < 0 {
-						p.sleep_time = 0;
-						// added again at ready_q time update
-						p.timings.0 -= self.timeslice.get() - remaining;
-						p.state = ProcessState::Ready;
-
-						// because multiple mutable references are not allowed
-						self.ready_q.push_back((*p).clone());
-					} else {
-						// safe susbstraction
-						p.sleep_time -= self.timeslice.get() - remaining;
-					}
-				}
-
-				// reset sleep_time, because other scheduler state is considered
-				self.sleep_time = 0;
-				self.sleep_q.retain(|p| p.sleep_time > 0);
-
-				if !self.ready_q.is_empty() {
-					let mut act_proc = self.ready_q.pop_front().unwrap();
-					// the current running process generated the syscall
-					act_proc.timings.1 += 1;
-					// only the running process increases the execution time
-					act_proc.timings.2 += self.timeslice.get() - remaining - 1;
-					self.ready_q.push_front(act_proc);
-				}
-
-				// time update for ready_q
-				for p in self.ready_q.iter_mut() {
-					p.timings.0 += self.timeslice.get() - remaining;
+				// the running process uses at least one time unit for the syscall
+				if !self.ready_q.is_empty() && remaining >= self.timeslice.get() {
+					return SyscallResult::InvalidArgument;
 				}
 
-				// time update for wait_q
-				for p in self.wait_q.iter_mut() {
-					p.timings.0 += self.timeslice.get() - remaining;
-				}
+				// the time passes only if a process was running
+				let elapsed = if self.ready_q.is_empty() {
+					0
+				} else {
+					self.timeslice.get() - remaining
+				};
+				self.elapse(elapsed, true);
 
 				// reset timeslice to default RoundRobin 5 value
 				self.timeslice = self.default_timeslice;
 
 				match syscall {
-					Syscall::Fork(priority) => {
+					Syscall::Fork(priority)
+					| Syscall::ForkClass { priority, .. }
+					| Syscall::ForkReserved { priority, .. } => {
 						// increasing pids
 						self.init_pid += 1;
 
 						// instantiate the new process
-						let new_proc = ProcessInfo {
-							pid: Pid::new(self.init_pid),
-							state: ProcessState::Ready,
-							timings: (0, 0, 0),
-							priority: priority,
-							sleep_time: 0,
-							extra: String::new(),
-						};
+						let mut new_proc = Pcb::new(
+							Pid::new(self.init_pid),
+							self.ready_q.front().map(|p| p.pid),
+							priority,
+							(),
+						);
+						// the child starts in the group of its parent
+						new_proc.group = self.ready_q.front().map_or(0, |p| p.group);
 
 						// this allows unwrap
 						if !self.ready_q.is_empty() {
 							let mut prev_proc = self.ready_q.pop_front().unwrap();
 
 							// move from running to ready state to be again planified
-							if remaining >= self.minimum_remaining_timeslice {
+							if self.continues(remaining).is_some() {
 								prev_proc.state = ProcessState::Ready;
 							}
 
@@ -268,13 +261,9 @@ impl Scheduler for RoundRobin {
 						self.ready_q.push_back(new_proc);
 
 						// change the timeslice
-						if remaining >= self.minimum_remaining_timeslice {
-							self.timeslice = NonZeroUsize::new(remaining).unwrap();
-						} else {
-							self.timeslice = self.default_timeslice;
-						}
+						self.timeslice = self.continues(remaining).unwrap_or(self.default_timeslice);
 
-						return SyscallResult::Pid(pid_return);
+						SyscallResult::Pid(pid_return)
 					},
 
 					Syscall::Wait(event_num) => {
@@ -283,83 +272,135 @@ impl Scheduler for RoundRobin {
 							return SyscallResult::NoRunningProcess;
 						}
 
-						let mut act_proc = self.ready_q.pop_front().unwrap();
+						let act_proc = self.ready_q.pop_front().unwrap();
+
+						// a latched event is consumed and the process does not block
+						if self.latched_events.remove(&event_num) {
+							self.replan(act_proc, remaining);
+							return SyscallResult::Success;
+						}
 
-						act_proc.state = ProcessState::Waiting { event: Some(event_num) };
-						self.wait_q.push_back(act_proc);
+						self.wait_q.push(event_num, act_proc);
 
-						return SyscallResult::Success;
+						SyscallResult::Success
 					},
 
-					Syscall::Sleep(t) => {
+					// the process sleeps, or waits for the device to complete the request
+					Syscall::Sleep(_) | Syscall::Io { .. } => {
+						let t = syscall.blocking_time().unwrap_or_default();
 						if self.ready_q.is_empty() {
 							return SyscallResult::NoRunningProcess;
 						}
 
 						let mut act_proc = self.ready_q.pop_front().unwrap();
 
-						// actualize the sleep_time field
-						act_proc.sleep_time = t;
+						// the process wakes up after t time units
+						act_proc.wakeup = self.clock.wakeup(t);
 						// the sleep state from the project documentation
 						act_proc.state = ProcessState::Waiting{ event: None };
 
-						self.sleep_q.push_back(act_proc);
-						return SyscallResult::Success;
+						self.sleep_q.push(act_proc, self.clock.now());
+						SyscallResult::Success
 					},
 
-					Syscall::Signal(event_num) => {
+					Syscall::Signal(event_num)
+					| Syscall::SignalOne(event_num)
+					| Syscall::SignalLatched(event_num) => {
 						if self.ready_q.is_empty() {
 							return SyscallResult::NoRunningProcess;
 						}
 
-						let mut act_process = self.ready_q.pop_front().unwrap();
-						// do not planify again the process that sent the signal
-						if remaining < self.minimum_remaining_timeslice {
-							act_process.state = ProcessState::Ready;
+						let act_process = self.ready_q.pop_front().unwrap();
+
+						// move the waiting processes to ready_queue
+						let limit = match syscall {
+							Syscall::SignalOne(_) => 1,
+							_ => usize::MAX,
+						};
+						let woken = self.wake_waiters(event_num, limit);
+
+						// nobody waits, so remember the event for the next wait
+						if woken == 0 && syscall == Syscall::SignalLatched(event_num) {
+							self.latched_events.insert(event_num);
 						}
 
-						// algorithm to move the waiting process to ready_queue
-						let mut len = self.wait_q.len();
-						let mut idx = 0;
-						let mut removed;
-
-						while idx < len {
-							removed = false;
-							// find the event the process is waiting for using match
-							match self.wait_q[idx].state {
-								ProcessState::Waiting { event: Some(src_num) } => {
-									if src_num == event_num {
-										let mut proc = self.wait_q.remove(idx).unwrap();
-										proc.state = ProcessState::Ready;
-
-										self.ready_q.push_back(proc);
-										len -= 1;
-										removed = true;
-									}
-								},
+						self.replan(act_process, remaining);
+						SyscallResult::Woken(woken)
+					},
 
-								_ => { },
-							}
+					Syscall::SetPriority(pid, priority) => {
+						if self.ready_q.is_empty() {
+							return SyscallResult::NoRunningProcess;
+						}
 
-							idx += 1;
-							// elements moved to the left, so increase idx
-							// to not skip elements
-							if removed {
-								idx -= 1;
+						let mut act_proc = self.ready_q.pop_front().unwrap();
+
+						// a process can change only its own priority or its children's
+						let result = if act_proc.pid == pid {
+							act_proc.priority = priority;
+							SyscallResult::Success
+						} else {
+							match self.find_mut(pid) {
+								None => SyscallResult::InvalidPid,
+								Some(proc) if proc.parent != Some(act_proc.pid) => {
+									SyscallResult::PermissionDenied
+								},
+								Some(proc) => {
+									proc.priority = priority;
+									SyscallResult::Success
+								},
 							}
+						};
+
+						// priorities are ignored, so no queue is reordered
+						self.replan(act_proc, remaining);
+						result
+					},
+
+					// classes are ignored, groups are only stored in the process
+					Syscall::SetClass(pid, _) | Syscall::SetGroup(pid, _) => {
+						if self.ready_q.is_empty() {
+							return SyscallResult::NoRunningProcess;
 						}
 
-						// change the timeslice
-						if act_process.state == ProcessState::Ready {
-							self.timeslice = self.default_timeslice;
-							self.ready_q.push_back(act_process);
+						let mut act_proc = self.ready_q.pop_front().unwrap();
+						let set = |proc: &mut Pcb| {
+							if let Syscall::SetGroup(_, group) = syscall {
+								proc.group = group;
+							}
+						};
+
+						let result = if act_proc.pid == pid {
+							set(&mut act_proc);
+							SyscallResult::Success
 						} else {
-							self.timeslice = NonZeroUsize::new(remaining).unwrap();
-							act_process.state = ProcessState::Ready;
-							self.ready_q.push_front(act_process);
+							match self.find_mut(pid) {
+								None => SyscallResult::InvalidPid,
+								Some(proc) if proc.parent != Some(act_proc.pid) => {
+									SyscallResult::PermissionDenied
+								},
+								Some(proc) => {
+									set(proc);
+									SyscallResult::Success
+								},
+							}
+						};
+
+						self.replan(act_proc, remaining);
+						result
+					},
+
+					Syscall::Yield => {
+						if self.ready_q.is_empty() {
+							return SyscallResult::NoRunningProcess;
 						}
 
-						return SyscallResult::Success;
+						// planned again only after all the other ready processes
+						let mut act_proc = self.ready_q.pop_front().unwrap();
+						act_proc.state = ProcessState::Ready;
+						self.ready_q.push_back(act_proc);
+
+						SyscallResult::Success
 					},
 
 					Syscall::Exit => {
@@ -370,44 +411,27 @@ impl Scheduler for RoundRobin {
 
 						let proc = self.ready_q.pop_front().unwrap();
 
-						// ok if the last process is the one with pid 1 and calls exit
-						if proc.pid() == 1 && self.ready_q.is_empty() && self.wait_q.is_empty()
-							&& self.sleep_q.is_empty() {
-							self.panic_state = false;
+						// with orphan adoption the others keep running, otherwise
+						// the proc pid 1 has to be the last to exit
+						if self.adopt_orphans {
+							self.reparent_children(proc.pid);
+						} else if proc.pid() == 1 && self.alive() > 0 {
+							self.panic = Some(PanicReason::InitExited { alive: self.alive() });
 						}
 
-						return SyscallResult::Success;
+						SyscallResult::Success
 					},
 				}
 			},
 
 			// the elapsed time is self.timeslice
 			StopReason::Expired => {
-				for p in self.sleep_q.iter_mut() {
-					p.timings.0 += self.timeslice.get();
-					if p.sleep_time as i32 - (self.timeslice.get() as i32) <= 0 {
-						p.state = ProcessState::Ready;
-						p.timings.0 -= self.timeslice.get();
-						p.sleep_time = 0;
-						self.ready_q.push_back((*p).clone());
-					} else {
-						p.sleep_time -= self.timeslice.get();
-					}
+				// only a running process can be preempted
+				if self.ready_q.is_empty() {
+					return SyscallResult::NoRunningProcess;
 				}
 
-				self.sleep_q.retain(|p| p.sleep_time > 0);
-
-				let mut act_proc = self.ready_q.pop_front().unwrap();
-				act_proc.timings.2 += self.timeslice.get();
-				self.ready_q.push_front(act_proc);
-
-				for p in self.ready_q.iter_mut() {
-					p.timings.0 += self.timeslice.get();
-				}
-
-				for p in self.wait_q.iter_mut() {
-					p.timings.0 += self.timeslice.get();
-				}
+				self.elapse(self.timeslice.get(), false);
 
 				self.timeslice = self.default_timeslice;
 
@@ -416,7 +440,7 @@ impl Scheduler for RoundRobin {
 						proc.state = ProcessState::Ready;
 						self.ready_q.push_back(proc);
 
-						return SyscallResult::Success;
+						SyscallResult::Success
 					},
 
 					None => SyscallResult::NoRunningProcess,
@@ -428,9 +452,10 @@ impl Scheduler for RoundRobin {
 	/// Used to display the processes in a pretty format
 	fn list(&mut self) -> Vec<&dyn crate::Process> {
 		let mut combine_procs: Vec<&dyn crate::Process> = Vec::new();
+		self.sleep_q.update(self.clock.now());
 
 		// add all processes to a vector
-		combine_procs.extend(self.sleep_q.iter().map(|proc| proc as &dyn Process));
+		combine_procs.extend(self.sleep_q.processes());
 		combine_procs.extend(self.wait_q.iter().map(|proc| proc as &dyn Process));
 		combine_procs.extend(self.ready_q.iter().map(|proc| proc as &dyn Process));
 
@@ -439,4 +464,14 @@ impl Scheduler for RoundRobin {
 
 		combine_procs
 	}
+
+	/// The switch time passes for all processes when the scheduler is called again
+	fn context_switch(&mut self, time: usize) {
+		self.clock.sleep(time);
+	}
+
+	/// The process waits for the device until the request completes
+	fn io_completion(&mut self, pid: Pid, wakeup: usize) {
+		self.sleep_q.set_wakeup(pid, wakeup);
+	}
 }
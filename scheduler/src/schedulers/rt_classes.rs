@@ -0,0 +1,144 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::num::NonZeroUsize;
+
+use crate::pcb::{Pcb, PcbExt};
+use crate::scheduler::{Pid, SchedClass, Syscall, SyscallResult};
+use crate::schedulers::dispatcher::{Dispatcher, Policy, Requeue};
+
+/// Scheduler with real-time classes: the ready [`SchedClass::Fifo`] and
+/// [`SchedClass::RoundRobin`] processes run by decreasing real-time priority,
+/// before the [`SchedClass::Other`] processes, that take turns like in a
+/// round robin.
+pub type RtClasses = Dispatcher<Classes>;
+
+/// The ready processes of a [`RtClasses`] scheduler.
+pub struct Classes {
+    /// The ready real-time processes, in one queue per priority.
+    pub rt: BTreeMap<u8, VecDeque<Pcb<SchedClass>>>,
+    /// The ready normal processes, in the order they became ready.
+    pub other: VecDeque<Pcb<SchedClass>>,
+    pub timeslice: NonZeroUsize,
+}
+
+impl Classes {
+    pub fn new(timeslice: NonZeroUsize) -> Classes {
+        Classes {
+            rt: BTreeMap::new(),
+            other: VecDeque::new(),
+            timeslice,
+        }
+    }
+
+    /// The real-time priority of the process, 0 for a normal process.
+    fn rank(process: &Pcb<SchedClass>) -> u8 {
+        process
+            .ext
+            .rt_priority()
+            .map_or(0, |priority| priority.get())
+    }
+
+    /// The rank of the process that runs next.
+    fn best(&self) -> Option<u8> {
+        match self.rt.last_key_value() {
+            Some((priority, _)) => Some(*priority),
+            None if !self.other.is_empty() => Some(0),
+            None => None,
+        }
+    }
+}
+
+impl Policy for Classes {
+    type Ext = SchedClass;
+
+    fn fork(&mut self, parent: Option<&Pcb<SchedClass>>, syscall: Syscall) -> SchedClass {
+        match syscall {
+            Syscall::ForkClass { class, .. } => class,
+            _ => parent.map(|parent| parent.ext).unwrap_or_default(),
+        }
+    }
+
+    fn push(&mut self, process: Pcb<SchedClass>, requeue: Requeue) {
+        let queue = match process.ext.rt_priority() {
+            Some(priority) => self.rt.entry(priority.get()).or_default(),
+            None => &mut self.other,
+        };
+        // a FIFO process is not time-sliced, and a real-time process that
+        // was preempted goes on before the others of its priority
+        let front = matches!(
+            (process.ext, requeue),
+            (SchedClass::Fifo(_), Requeue::Expired | Requeue::Preempted)
+                | (SchedClass::RoundRobin(_), Requeue::Preempted)
+        );
+        if front {
+            queue.push_front(process);
+        } else {
+            queue.push_back(process);
+        }
+    }
+
+    fn pop(&mut self) -> Option<Pcb<SchedClass>> {
+        let Some(mut entry) = self.rt.last_entry() else {
+            return self.other.pop_front();
+        };
+        let process = entry.get_mut().pop_front();
+        if entry.get().is_empty() {
+            entry.remove();
+        }
+        process
+    }
+
+    fn remove(&mut self, pid: Pid) -> Option<Pcb<SchedClass>> {
+        if let Some(index) = self.other.iter().position(|process| process.pid == pid) {
+            return self.other.remove(index);
+        }
+        let (priority, index) = self.rt.iter().find_map(|(priority, queue)| {
+            let index = queue.iter().position(|process| process.pid == pid)?;
+            Some((*priority, index))
+        })?;
+        let queue = self.rt.get_mut(&priority).unwrap();
+        let process = queue.remove(index);
+        if queue.is_empty() {
+            self.rt.remove(&priority);
+        }
+        process
+    }
+
+    fn preempts(&self, running: &Pcb<SchedClass>) -> bool {
+        self.best()
+            .is_some_and(|best| best > Classes::rank(running))
+    }
+
+    fn timeslice(&self, _process: &Pcb<SchedClass>) -> NonZeroUsize {
+        self.timeslice
+    }
+
+    fn set(&mut self, process: &mut Pcb<SchedClass>, syscall: Syscall) -> SyscallResult {
+        match syscall {
+            Syscall::SetPriority(_, priority) => process.priority = priority,
+            Syscall::SetClass(_, class) => process.ext = class,
+            Syscall::SetGroup(_, group) => process.group = group,
+            _ => {}
+        }
+        SyscallResult::Success
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = &Pcb<SchedClass>> + '_> {
+        Box::new(self.rt.values().rev().flatten().chain(self.other.iter()))
+    }
+
+    fn iter_mut(&mut self) -> Box<dyn Iterator<Item = &mut Pcb<SchedClass>> + '_> {
+        Box::new(
+            self.rt
+                .values_mut()
+                .rev()
+                .flatten()
+                .chain(self.other.iter_mut()),
+        )
+    }
+}
+
+impl PcbExt for SchedClass {
+    fn extra(&self) -> String {
+        self.to_string()
+    }
+}
@@ -0,0 +1,210 @@
+//! The sleeping processes, ordered by their wakeup time.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::pcb::{Pcb, PcbExt};
+use crate::scheduler::{Pid, Process, ProcessState};
+
+/// The sleeping processes of a policy, keyed by their absolute
+/// [wakeup time](Pcb::wakeup).
+///
+/// Adding a process and waking up the first one take O(log n). Processes
+/// with the same wakeup time wake up in the order they were added.
+///
+/// The time the processes sleep is added to their total time when they
+/// wake up, so the clock does not have to visit every sleeper whenever time
+/// passes. [`SleepQueue::processes`] lists them with the time they have
+/// slept until the last [`SleepQueue::update`].
+///
+/// ## Example
+///
+/// ```rust
+/// use scheduler::{Pcb, Pid, SleepQueue};
+///
+/// let mut sleepers = SleepQueue::default();
+/// for (pid, wakeup) in [(2, 7), (3, 4), (4, 7)] {
+///     let mut process = Pcb::new(Pid::new(pid), None, 0, ());
+///     process.wakeup = wakeup;
+///     sleepers.push(process, 1);
+/// }
+///
+/// assert_eq!(sleepers.next_wakeup(), Some(4));
+/// let woken = sleepers.pop_due(5).unwrap();
+/// assert_eq!((woken.pid, woken.timings.0), (Pid::new(3), 4));
+/// assert!(sleepers.pop_due(5).is_none());
+/// assert_eq!(sleepers.pop_due(7).map(|process| process.pid), Some(Pid::new(2)));
+/// ```
+#[derive(Debug)]
+pub struct SleepQueue<Ext: PcbExt = ()> {
+    sleepers: BTreeMap<(usize, u64), Sleeper<Ext>>,
+    added: u64,
+
+    /// The time of the last update, shared with the sleepers.
+    now: Arc<AtomicUsize>,
+}
+
+/// A sleeping process and the time until which its total time is counted.
+#[derive(Debug)]
+struct Sleeper<Ext: PcbExt> {
+    process: Pcb<Ext>,
+    since: usize,
+    now: Arc<AtomicUsize>,
+}
+
+impl<Ext: PcbExt> Default for SleepQueue<Ext> {
+    fn default() -> SleepQueue<Ext> {
+        SleepQueue {
+            sleepers: BTreeMap::new(),
+            added: 0,
+            now: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+}
+
+/// The clone has its own time, the sleepers are not shared.
+impl<Ext: PcbExt + Clone> Clone for SleepQueue<Ext> {
+    fn clone(&self) -> SleepQueue<Ext> {
+        let now = Arc::new(AtomicUsize::new(self.now.load(Ordering::Relaxed)));
+        let sleepers = self
+            .sleepers
+            .iter()
+            .map(|(key, sleeper)| {
+                let sleeper = Sleeper {
+                    process: sleeper.process.clone(),
+                    since: sleeper.since,
+                    now: now.clone(),
+                };
+                (*key, sleeper)
+            })
+            .collect();
+        SleepQueue {
+            sleepers,
+            added: self.added,
+            now,
+        }
+    }
+}
+
+impl<Ext: PcbExt> SleepQueue<Ext> {
+    /// Add a process that sleeps from `now` until its wakeup time.
+    pub fn push(&mut self, process: Pcb<Ext>, now: usize) {
+        let sleeper = Sleeper {
+            process,
+            since: now,
+            now: self.now.clone(),
+        };
+        self.sleepers
+            .insert((sleeper.process.wakeup, self.added), sleeper);
+        self.added += 1;
+    }
+
+    /// Remove the first process to wake up, if its wakeup time is not later
+    /// than `now`, with the time it has slept until `now` added.
+    pub fn pop_due(&mut self, now: usize) -> Option<Pcb<Ext>> {
+        let entry = self.sleepers.first_entry()?;
+        if entry.key().0 <= now {
+            let Sleeper {
+                mut process, since, ..
+            } = entry.remove();
+            process.elapse(now - since);
+            Some(process)
+        } else {
+            None
+        }
+    }
+
+    /// Let [`SleepQueue::processes`] count the time the processes have
+    /// slept until `now`, without visiting them.
+    pub fn update(&mut self, now: usize) {
+        self.now.store(now, Ordering::Relaxed);
+    }
+
+    /// Change the wakeup time of a sleeping process, the time it has slept
+    /// is kept. Returns whether the process sleeps.
+    pub fn set_wakeup(&mut self, pid: Pid, wakeup: usize) -> bool {
+        let Some(key) = self
+            .sleepers
+            .iter()
+            .find(|(_, sleeper)| sleeper.process.pid == pid)
+            .map(|(key, _)| *key)
+        else {
+            return false;
+        };
+        let mut sleeper = self.sleepers.remove(&key).unwrap();
+        sleeper.process.wakeup = wakeup;
+        self.sleepers.insert((wakeup, key.1), sleeper);
+        true
+    }
+
+    /// The earliest wakeup time.
+    pub fn next_wakeup(&self) -> Option<usize> {
+        self.sleepers.keys().next().map(|(wakeup, _)| *wakeup)
+    }
+
+    /// Whether the process sleeps.
+    pub fn contains(&self, pid: Pid) -> bool {
+        self.iter().any(|process| process.pid == pid)
+    }
+
+    /// The number of sleeping processes.
+    pub fn len(&self) -> usize {
+        self.sleepers.len()
+    }
+
+    /// Whether no process sleeps.
+    pub fn is_empty(&self) -> bool {
+        self.sleepers.is_empty()
+    }
+
+    /// The processes, in the order they wake up. Their total time does not
+    /// count the time they have slept yet.
+    pub fn iter(&self) -> impl Iterator<Item = &Pcb<Ext>> {
+        self.sleepers.values().map(|sleeper| &sleeper.process)
+    }
+
+    /// The processes, in the order they wake up. The wakeup time cannot be
+    /// changed, the process keeps its place.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Pcb<Ext>> {
+        self.sleepers
+            .values_mut()
+            .map(|sleeper| &mut sleeper.process)
+    }
+
+    /// The processes, in the order they wake up, with the time they have
+    /// slept until the last [`SleepQueue::update`] in their total time.
+    pub fn processes(&self) -> impl Iterator<Item = &dyn Process> {
+        self.sleepers
+            .values()
+            .map(|sleeper| sleeper as &dyn Process)
+    }
+}
+
+impl<Ext: PcbExt> Process for Sleeper<Ext> {
+    fn pid(&self) -> Pid {
+        self.process.pid
+    }
+
+    fn state(&self) -> ProcessState {
+        self.process.state
+    }
+
+    fn timings(&self) -> (usize, usize, usize) {
+        let (total, syscall, execute) = self.process.timings;
+        let slept = self.now.load(Ordering::Relaxed).saturating_sub(self.since);
+        (total + slept, syscall, execute)
+    }
+
+    fn priority(&self) -> i8 {
+        self.process.priority
+    }
+
+    fn extra(&self) -> String {
+        self.process.extra()
+    }
+
+    fn group(&self) -> usize {
+        self.process.group
+    }
+}
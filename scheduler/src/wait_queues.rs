@@ -0,0 +1,108 @@
+//! The processes waiting for events, in one queue per event.
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::pcb::{Pcb, PcbExt};
+use crate::scheduler::{Pid, ProcessState};
+
+/// The processes waiting for events, indexed by the event number.
+///
+/// Every event has its own FIFO queue, so a signal only visits the
+/// processes it wakes up.
+///
+/// ## Example
+///
+/// ```rust
+/// use scheduler::{Pcb, Pid, WaitQueues};
+///
+/// let mut waiting = WaitQueues::default();
+/// for (pid, event) in [(2, 1), (3, 5), (4, 1)] {
+///     waiting.push(event, Pcb::new(Pid::new(pid), None, 0, ()));
+/// }
+///
+/// assert_eq!(waiting.waiting_for(Pid::new(3)), Some(5));
+/// assert_eq!(waiting.waiters(1).map(|process| process.pid).collect::<Vec<_>>(), [2, 4]);
+/// assert_eq!(waiting.wake(1, 1)[0].pid, Pid::new(2));
+/// assert_eq!(waiting.events(), [1, 5]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct WaitQueues<Ext: PcbExt = ()> {
+    queues: HashMap<usize, VecDeque<Pcb<Ext>>>,
+}
+
+impl<Ext: PcbExt> Default for WaitQueues<Ext> {
+    fn default() -> WaitQueues<Ext> {
+        WaitQueues {
+            queues: HashMap::new(),
+        }
+    }
+}
+
+impl<Ext: PcbExt> WaitQueues<Ext> {
+    /// Add a process at the back of the queue of the event, in the
+    /// [`ProcessState::Waiting`] state.
+    pub fn push(&mut self, event: usize, mut process: Pcb<Ext>) {
+        process.state = ProcessState::Waiting { event: Some(event) };
+        self.queues.entry(event).or_default().push_back(process);
+    }
+
+    /// Remove at most `limit` processes waiting for the event, in the order
+    /// they started waiting, and return them in the [`ProcessState::Ready`] state.
+    pub fn wake(&mut self, event: usize, limit: usize) -> Vec<Pcb<Ext>> {
+        let Some(queue) = self.queues.get_mut(&event) else {
+            return vec![];
+        };
+        let count = limit.min(queue.len());
+        let woken = queue
+            .drain(..count)
+            .map(|mut process| {
+                process.state = ProcessState::Ready;
+                process
+            })
+            .collect();
+        if queue.is_empty() {
+            self.queues.remove(&event);
+        }
+        woken
+    }
+
+    /// The processes waiting for the event, in the order they started waiting.
+    pub fn waiters(&self, event: usize) -> impl Iterator<Item = &Pcb<Ext>> {
+        self.queues.get(&event).into_iter().flatten()
+    }
+
+    /// The event that the process waits for.
+    pub fn waiting_for(&self, pid: Pid) -> Option<usize> {
+        self.queues
+            .iter()
+            .find(|(_, queue)| queue.iter().any(|process| process.pid == pid))
+            .map(|(event, _)| *event)
+    }
+
+    /// The events that processes wait for, in increasing order.
+    pub fn events(&self) -> Vec<usize> {
+        let mut events = self.queues.keys().copied().collect::<Vec<usize>>();
+        events.sort();
+        events
+    }
+
+    /// The number of waiting processes.
+    pub fn len(&self) -> usize {
+        self.queues.values().map(VecDeque::len).sum()
+    }
+
+    /// Whether no process waits.
+    pub fn is_empty(&self) -> bool {
+        self.queues.is_empty()
+    }
+
+    /// All the waiting processes, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &Pcb<Ext>> {
+        self.queues.values().flatten()
+    }
+
+    /// All the waiting processes, in no particular order.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Pcb<Ext>> {
+        self.queues.values_mut().flatten()
+    }
+}